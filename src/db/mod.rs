@@ -28,10 +28,43 @@ pub struct Unique {
     pub en: String,
 }
 
+/// A prefix or suffix of names, both magic affixes and parts of rare names.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Affix {
+    pub zh: String,
+    pub en: String,
+}
+
+/// The variants of a unique in PoB, the name is En.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UniqueVariants {
+    pub name: String,
+    pub variants: Vec<UniqueVariant>,
+}
+
+/// A variant of a unique, the mods are En and may contain ranges like "(10-20)".
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UniqueVariant {
+    pub name: String,
+    pub mods: Vec<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Gem {
     pub zh: String,
     pub en: String,
+    /// The description, i.e. `secDescrText` of gems.
+    #[serde(rename = "descrZh", default, skip_serializing_if = "Option::is_none")]
+    pub descr_zh: Option<String>,
+    #[serde(rename = "descrEn", default, skip_serializing_if = "Option::is_none")]
+    pub descr_en: Option<String>,
+}
+
+/// A skill granted by items, the id is the granted effect id in PoB.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GrantedSkill {
+    pub id: String,
+    pub en: String,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -83,10 +116,31 @@ pub struct RequirementSuffix {
 pub struct Stat {
     pub zh: String,
     pub en: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ids: Vec<String>,
+}
+
+/// A tier of the mod, `en` is the En template of the stat like `Stat::en`.
+///
+/// The ranges are indexed by parameter numbers of the template.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ModTier {
+    pub id: String,
+    pub en: String,
+    pub tier: i32,
+    pub ranges: Vec<ModRange>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ModRange {
+    pub min: f64,
+    pub max: f64,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Assets {
+    #[serde(default)]
+    pub version: String,
     pub amulets: Vec<BaseType>,
     pub belts: Vec<BaseType>,
     pub rings: Vec<BaseType>,
@@ -114,7 +168,21 @@ pub struct Assets {
     pub notables: Vec<Node>,
     pub stats: Vec<Stat>,
     pub tattoos: Vec<BaseType>,
-    pub grafts: Vec<BaseType>,
+    pub grafts: Vec<BaseType>, // runegrafts
+    #[serde(default)]
+    pub prefixes: Vec<Affix>,
+    #[serde(default)]
+    pub suffixes: Vec<Affix>,
+    #[serde(rename = "rarePrefixes", default)]
+    pub rare_prefixes: Vec<Affix>,
+    #[serde(rename = "rareSuffixes", default)]
+    pub rare_suffixes: Vec<Affix>,
+    #[serde(rename = "uniqueVariants", default)]
+    pub unique_variants: Vec<UniqueVariants>,
+    #[serde(rename = "modTiers", default)]
+    pub mod_tiers: Vec<ModTier>,
+    #[serde(rename = "grantedSkills", default)]
+    pub granted_skills: Vec<GrantedSkill>,
 }
 
 #[cfg(test)]
@@ -7,6 +7,7 @@ use poe_trans::{
 use serde::{Deserialize, Serialize};
 use std::io::prelude::*;
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 use warp::Filter;
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -35,7 +36,15 @@ async fn main() {
             translator.trans_items(&mut json.items);
             translator.trans_passive_skills(&mut json.passive_skills);
 
-            let transformer = Transformer::new(json.items, json.passive_skills, Options::default());
+            let options = Options {
+                export_time: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .ok()
+                    .map(|x| x.as_secs()),
+                untranslated: translator.untranslated(),
+                ..Default::default()
+            };
+            let transformer = Transformer::new(json.items, json.passive_skills, options);
             let build_xml = transformer.transform().to_string();
             let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
             encoder.write_all(build_xml.as_bytes()).unwrap();
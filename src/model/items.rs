@@ -13,8 +13,8 @@ pub struct Character {
     pub class: String,
     pub league: String,
     pub level: i32,
-    name: String,
-    realm: String,
+    pub name: String,
+    pub realm: String,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -47,6 +47,13 @@ pub struct Item {
     pub properties: Option<Vec<ItemProperty>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub requirements: Option<Vec<ItemRequirement>>,
+    #[serde(
+        rename = "additionalProperties",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub additional_properties: Option<Vec<ItemProperty>>, // exist in gems
+    #[serde(rename = "secDescrText", skip_serializing_if = "Option::is_none")]
+    pub sec_descr_text: Option<String>, // exist in gems
     #[serde(rename = "enchantMods", skip_serializing_if = "Option::is_none")]
     pub enchant_mods: Option<Vec<String>>,
     #[serde(rename = "implicitMods", skip_serializing_if = "Option::is_none")]
@@ -63,12 +70,24 @@ pub struct Item {
     pub scourge_mods: Option<Vec<String>>,
     #[serde(rename = "crucibleMods", skip_serializing_if = "Option::is_none")]
     pub crucible_mods: Option<Vec<String>>,
+    #[serde(rename = "mutatedMods", skip_serializing_if = "Option::is_none")]
+    pub mutated_mods: Option<Vec<String>>,
+    #[serde(rename = "veiledMods", skip_serializing_if = "Option::is_none")]
+    pub veiled_mods: Option<Vec<String>>, // ids of veiled mods, there is no text to translate
+    #[serde(rename = "memoryStrands", skip_serializing_if = "Option::is_none")]
+    pub memory_strands: Option<i32>, // not supported by PoB
     #[serde(rename = "inventoryId", skip_serializing_if = "Option::is_none")]
     pub inventory_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    duplicated: Option<bool>,
+    pub duplicated: Option<bool>, // mirrored
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub synthesised: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub split: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    synthesised: Option<bool>,
+    pub unmodifiable: Option<bool>,
+    #[serde(rename = "foilVariation", skip_serializing_if = "Option::is_none")]
+    pub foil_variation: Option<i32>, // exist in relics
     #[serde(rename = "socketedItems", skip_serializing_if = "Option::is_none")]
     pub socketed_items: Option<Vec<Box<Item>>>, // exist in equipments
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -86,6 +105,8 @@ pub struct Item {
 pub struct ItemProperty {
     pub name: String,
     pub values: Vec<ItemPropertyValueType>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub type_v: Option<i32>,
     #[serde(flatten)]
     other: IndexMap<String, Value>,
 }
@@ -165,6 +165,10 @@ pub struct SkillOverride {
     pub name: String,
     #[serde(rename = "isKeystone", skip_serializing_if = "Option::is_none")]
     pub is_keystone: Option<bool>,
+    #[serde(rename = "isTattoo", skip_serializing_if = "Option::is_none")]
+    pub is_tattoo: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stats: Option<Vec<String>>,
     #[serde(flatten)]
     other: IndexMap<String, Value>,
 }
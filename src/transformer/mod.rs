@@ -5,15 +5,17 @@ use std::{
 };
 
 use indexmap::IndexMap;
+use log::warn;
 use support::{
     get_class_and_ascendancy, is_phrecia_ascendancy, CLUSTER_JEWEL_SIZE_LARGE,
     CLUSTER_JEWEL_SIZE_MEDIUM,
 };
 use xml::{
     items::Item,
+    notes::Notes,
     path_of_building::PathOfBuilding,
     skills::{Gem, Skill},
-    slot::{Slot, SlotItem},
+    slot::{Slot, SlotItem, SocketIdURL},
     tree::{MasteryEffect, Override, Socket},
 };
 
@@ -24,16 +26,33 @@ mod xml;
 
 pub struct Options {
     pub skip_weapon2: bool,
+    /// Whether flasks are active in PoB.
+    pub flask_active: bool,
+    /// Whether tinctures are active in PoB.
+    pub tincture_active: bool,
+    /// The main socket group starts from 1, it is selected by heuristics if None.
+    pub main_socket_group: Option<usize>,
+    /// The unix time of exporting, it is written into notes.
+    pub export_time: Option<u64>,
+    /// The strings failed to be translated, they are written into notes.
+    pub untranslated: Vec<String>,
 }
 
 impl Default for Options {
     fn default() -> Self {
-        Self { skip_weapon2: true }
+        Self {
+            skip_weapon2: true,
+            flask_active: true,
+            tincture_active: true,
+            main_socket_group: None,
+            export_time: None,
+            untranslated: vec![],
+        }
     }
 }
 
 pub struct Transformer {
-    supporter: support::Supporter,
+    supporter: support::Supporter<'static>,
     items: model::items::Items,
     skills: model::passive_skills::PassiveSkills,
     options: Options,
@@ -64,9 +83,26 @@ impl Transformer {
         build.class_name = class_name.to_string();
         build.ascend_class_name = ascend_name.to_string();
 
+        building.notes = Notes {
+            character_name: character.name.clone(),
+            league: character.league.clone(),
+            realm: character.realm.clone(),
+            level: character.level,
+            export_time: self.options.export_time,
+            assets_version: self.supporter.assets_version().to_string(),
+            untranslated: self.options.untranslated.clone(),
+        };
+
         self.parse_items(&mut item_id_gen, &mut building);
         self.parse_tree(&mut item_id_gen, &mut building);
 
+        let main_socket_group = select_main_socket_group(&mut building.skills.skill_set.skills);
+        building.build.main_socket_group = self
+            .options
+            .main_socket_group
+            .or(main_socket_group)
+            .unwrap_or(1);
+
         building
     }
 
@@ -76,19 +112,44 @@ impl Transformer {
             let item_id = item_id_gen.clone();
             *item_id_gen += 1;
 
-            let item = Item::new(item_id as usize, data);
+            let item = self.new_item(item_id as usize, data);
+            let source = format!("Item:{}:{}", item_id, item.full_name());
             building.items.items.push(item);
 
-            let slot_name =
-                support::get_slot_name(data.inventory_id.as_ref().unwrap(), data.x.unwrap());
-            building
-                .items
-                .item_set
-                .slots
-                .push(SlotItem::Slot(Slot::new_equipment(
-                    &slot_name,
-                    item_id as i32,
-                )));
+            let inventory_id = data.inventory_id.as_ref().unwrap();
+            let slot_name = support::get_slot_name(inventory_id, data.x.unwrap());
+            let slot = if support::is_flask_inventory(inventory_id) {
+                let active = if support::is_tincture(&data.base_type) {
+                    self.options.tincture_active
+                } else {
+                    self.options.flask_active
+                };
+                Slot::new_flask(&slot_name, item_id, active)
+            } else {
+                Slot::new_equipment(&slot_name, item_id as i32)
+            };
+            building.items.item_set.slots.push(SlotItem::Slot(slot));
+
+            let mods: Vec<&String> = [
+                &data.enchant_mods,
+                &data.implicit_mods,
+                &data.explicit_mods,
+                &data.fractured_mods,
+                &data.crafted_mods,
+            ]
+            .into_iter()
+            .flatten()
+            .flatten()
+            .collect();
+            for (name, level) in support::get_granted_skills(&mods) {
+                let mut skill = Skill::new_granted(&slot_name, &source);
+                let skill_id = self.supporter.get_granted_skill_id(&name);
+                if skill_id.is_none() && !self.supporter.is_known_gem(&name) {
+                    warn!("unknown granted skill in PoB, {0}", name);
+                }
+                skill.gems.push(Gem::new_granted(&name, level, skill_id));
+                building.skills.skill_set.skills.push(skill);
+            }
 
             if let (Some(socketed_items), Some(sockets)) = (&data.socketed_items, &data.sockets) {
                 if sockets.len() > 0 && socketed_items.len() > 0 {
@@ -105,7 +166,7 @@ impl Transformer {
                             let item_id = item_id_gen.clone();
                             *item_id_gen += 1;
 
-                            let item = Item::new(item_id as usize, si);
+                            let item = self.new_item(item_id as usize, si);
                             building.items.items.push(item);
 
                             let si_slot_name =
@@ -120,7 +181,7 @@ impl Transformer {
                             } else {
                                 let mut skill = Skill::new(&slot_name);
                                 for si in &group {
-                                    skill.gems.push(Gem::new(*si));
+                                    skill.gems.push(self.new_gem(si));
                                 }
                                 skills.push(skill);
                                 prev_group_num = group_num;
@@ -132,7 +193,7 @@ impl Transformer {
                     if group.len() > 0 {
                         let mut skill = Skill::new(&slot_name);
                         for si in &group {
-                            skill.gems.push(Gem::new(*si));
+                            skill.gems.push(self.new_gem(si));
                         }
                         skills.push(skill);
                     }
@@ -141,6 +202,36 @@ impl Transformer {
         }
     }
 
+    fn new_gem(&self, data: &model::items::Item) -> Gem {
+        let name = self.supporter.get_gem_name(data);
+        if !self.supporter.is_known_gem(&name) {
+            warn!("unknown gem in PoB, {0}", name);
+        }
+
+        Gem::new(data, name)
+    }
+
+    fn new_item(&self, id: usize, data: &model::items::Item) -> Item {
+        let mut item = Item::new(id, data);
+
+        if item.is_unique() {
+            let variants = self
+                .supporter
+                .get_unique_variants(item.name(), &item.mods());
+            if let Some((variants, selected)) = variants {
+                item.set_variants(variants, selected);
+            }
+        } else {
+            for mod_str in item.explicit_mods_mut() {
+                if let Some(ranged) = self.supporter.get_ranged_mod(mod_str) {
+                    *mod_str = ranged;
+                }
+            }
+        }
+
+        item
+    }
+
     fn get_building_items(&self) -> Vec<&model::items::Item> {
         self.items
             .items
@@ -169,13 +260,16 @@ impl Transformer {
             let item_id = item_id_gen.clone();
             *item_id_gen += 1;
 
-            let item = Item::new(item_id as usize, data);
+            let item = self.new_item(item_id as usize, data);
             building.items.items.push(item);
 
-            let socket = Socket::new(
-                support::node_id_of_expansion_slot(data.x.unwrap() as usize),
-                item_id,
-            );
+            // the seq num also covers the sockets of cluster jewels
+            let node_id = support::node_id_of_expansion_slot(data.x.unwrap() as usize);
+            let slots = &mut building.items.item_set.slots;
+            slots.push(SlotItem::Slot(Slot::new_jewel(node_id, item_id)));
+            slots.push(SlotItem::SocketIdURL(SocketIdURL::new(node_id)));
+
+            let socket = Socket::new(node_id, item_id);
             spec.sockets.sockets.push(socket);
         }
 
@@ -204,7 +298,9 @@ impl Transformer {
             .append(&mut self.get_enabled_node_ids_of_jewels());
 
         for over in &self.skills.skill_overrides {
-            spec.overrides.push(Override::new(&over.1.name, *over.0));
+            let stats = over.1.stats.clone().unwrap_or_default();
+            spec.overrides
+                .push(Override::new(&over.1.name, *over.0, stats));
         }
     }
 
@@ -581,6 +677,47 @@ fn translate_oidx(src_oidx: i32, src_nodes_per_orbit: i32, dest_nodes_per_orbit:
     }
 }
 
+static MAIN_SKILL_SLOTS: [&str; 2] = ["Body Armour", "Weapon 1"];
+
+/// Select the main active skill of each group, and return the main socket group starting from 1.
+///
+/// The main group prefers body armour and weapon, then more links, then higher gem level.
+/// Groups with only utility skills, e.g. movement skills and auras, are skipped.
+fn select_main_socket_group(skills: &mut [Skill]) -> Option<usize> {
+    let mut best: Option<(usize, (bool, usize, i32))> = None;
+
+    for (i, skill) in skills.iter_mut().enumerate() {
+        // (index of active skills starting from 1, level)
+        let mut main: Option<(usize, i32)> = None;
+        let mut index = 0;
+        for gem in skill.gems.iter().filter(|x| !x.is_support()) {
+            // a Vaal gem grants the Vaal skill and the normal one, the normal one is selected
+            index += if gem.is_vaal_gem() { 2 } else { 1 };
+            if support::is_utility_skill(gem.name()) {
+                continue;
+            }
+            if main.is_none_or(|(_, level)| gem.level() > level) {
+                main = Some((index, gem.level()));
+            }
+        }
+
+        if let Some((index, level)) = main {
+            skill.set_main_active_skill(index);
+
+            let score = (
+                MAIN_SKILL_SLOTS.contains(&skill.slot()),
+                skill.gems.len(),
+                level,
+            );
+            if best.is_none_or(|(_, s)| score > s) {
+                best = Some((i + 1, score));
+            }
+        }
+    }
+
+    best.map(|(i, _)| i)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -602,4 +739,133 @@ mod tests {
 
         let _ = fs::write("test/building.xml", building.to_string());
     }
+
+    /// A sample of gem names in PoB, it must not be derived from assets.
+    static POB_GEM_NAMES: [&str; 8] = [
+        "Ice Nova",
+        "Ice Nova of Frostbolts",
+        "Vaal Ice Nova",
+        "Multistrike",
+        "Cyclone",
+        "Brutality",
+        "Grace",
+        "Vaal Grace",
+    ];
+
+    /// Every gem name the transformer emits should be recognized by PoB.
+    #[test]
+    fn test_gem_names() {
+        let gem = |name: &str| serde_json::json!({"name": "", "typeLine": name, "baseType": name, "ilvl": 0, "frameType": 4});
+        let vaal_gem = |name: &str, hybrid: &str| {
+            let mut gem = gem(name);
+            gem["hybrid"] = serde_json::json!({"baseTypeName": hybrid, "isVaalGem": true});
+            gem
+        };
+        let socket =
+            |group: usize| serde_json::json!({"group": group, "attr": "I", "sColour": "B"});
+
+        let items: model::items::Items = serde_json::from_value(serde_json::json!({
+            "items": [
+                {
+                    "id": "abc", "name": "", "typeLine": "Vaal Regalia", "baseType": "Vaal Regalia",
+                    "ilvl": 86, "frameType": 2, "inventoryId": "BodyArmour", "x": 0, "y": 0,
+                    "sockets": [socket(0), socket(0), socket(0), socket(1)],
+                    "socketedItems": [
+                        gem("Ice Nova of Frostbolts"),
+                        gem("Multistrike Support"),
+                        vaal_gem("Vaal Ice Nova", "Ice Nova"),
+                        vaal_gem("Vaal Grace", "Grace"),
+                    ],
+                },
+                {
+                    "id": "def", "name": "", "typeLine": "Vaal Axe", "baseType": "Vaal Axe",
+                    "ilvl": 86, "frameType": 2, "inventoryId": "Weapon", "x": 0, "y": 0,
+                    "sockets": [socket(0), socket(0)],
+                    "socketedItems": [gem("Cyclone"), gem("Brutality Support")],
+                },
+            ],
+            "character": {"class": "Witch", "league": "Standard", "level": 90, "name": "Foo", "realm": "pc"},
+        }))
+        .unwrap();
+        let skills: model::passive_skills::PassiveSkills =
+            serde_json::from_value(serde_json::json!({
+                "character": 3, "ascendancy": 0, "alternate_ascendancy": 0,
+                "hashes": [], "hashes_ex": [], "mastery_effects": {},
+                "skill_overrides": {}, "items": [], "jewel_data": {},
+            }))
+            .unwrap();
+
+        let building = Transformer::new(items, skills, Options::default())
+            .transform()
+            .to_string();
+        let re = regex::Regex::new(r#"nameSpec="([^"]*)""#).unwrap();
+        let names: Vec<&str> = re
+            .captures_iter(&building)
+            .map(|x| x.get(1).unwrap().as_str())
+            .collect();
+        assert_eq!(names.len(), 6);
+
+        let unknown: Vec<&&str> = names
+            .iter()
+            .filter(|x| !POB_GEM_NAMES.contains(x))
+            .collect();
+        assert!(unknown.is_empty(), "unknown gems: {:?}", unknown);
+    }
+
+    #[test]
+    fn test_select_main_socket_group() {
+        let new_gem = |name: &str, level: i32| {
+            let data: model::items::Item = serde_json::from_value(serde_json::json!({
+                "name": "", "typeLine": name, "baseType": name, "ilvl": 0, "frameType": 4,
+                "properties": [{"name": "Level", "values": [[level.to_string(), 0]], "displayMode": 0}],
+            }))
+            .unwrap();
+            Gem::new(&data, name.replace(" Support", ""))
+        };
+        let new_skill = |slot: &str, gems: &[(&str, i32)]| {
+            let mut skill = Skill::new(slot);
+            skill.gems = gems.iter().map(|(n, l)| new_gem(n, *l)).collect();
+            skill
+        };
+
+        let mut skills = vec![
+            new_skill(
+                "Boots",
+                &[("Flame Dash", 20), ("Faster Casting Support", 20)],
+            ),
+            new_skill(
+                "Gloves",
+                &[("Cyclone", 20), ("Melee Physical Damage Support", 20)],
+            ),
+            new_skill(
+                "Body Armour",
+                &[
+                    ("Herald of Ash", 20),
+                    ("Cyclone", 21),
+                    ("Brutality Support", 20),
+                    ("Melee Physical Damage Support", 20),
+                ],
+            ),
+            new_skill("Helmet", &[("Anger", 20), ("Wrath", 20)]),
+        ];
+        assert_eq!(select_main_socket_group(&mut skills), Some(3));
+        assert!(skills[2].to_string().contains(r#"mainActiveSkill="2""#));
+        assert!(skills[0].to_string().contains(r#"mainActiveSkill="nil""#));
+        assert!(skills[3].to_string().contains(r#"mainActiveSkill="nil""#));
+
+        let mut skills = vec![new_skill("Helmet", &[("Anger", 20)])];
+        assert_eq!(select_main_socket_group(&mut skills), None);
+
+        // the Vaal gem grants two active skills
+        let mut skills = vec![new_skill(
+            "Body Armour",
+            &[("Vaal Ice Nova", 20), ("Ice Nova", 21)],
+        )];
+        assert_eq!(select_main_socket_group(&mut skills), Some(1));
+        assert!(skills[0].to_string().contains(r#"mainActiveSkill="3""#));
+
+        let mut skills = vec![new_skill("Body Armour", &[("Vaal Ice Nova", 20)])];
+        assert_eq!(select_main_socket_group(&mut skills), Some(1));
+        assert!(skills[0].to_string().contains(r#"mainActiveSkill="2""#));
+    }
 }
@@ -1,9 +1,17 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::OnceLock,
+};
 
-use phf::phf_map;
+use phf::{phf_map, phf_set};
+use regex::Regex;
 use serde::Deserialize;
 
-use crate::model;
+use crate::{
+    db::{assets::ASSETS_DATA, Gem, GrantedSkill, ModTier, UniqueVariants},
+    model,
+    translator::util::stat::Template,
+};
 
 static CLASSES: [&str; 7] = [
     "Scion", "Marauder", "Ranger", "Witch", "Duelist", "Templar", "Shadow",
@@ -154,19 +162,172 @@ pub struct Constants {
     pub skills_per_orbit: Vec<i32>,
 }
 
-pub struct Supporter {
+/// The parts of assets used by transformer.
+#[derive(Deserialize)]
+struct Assets {
+    #[serde(default)]
+    version: String,
+    #[serde(rename = "uniqueVariants", default)]
+    unique_variants: Vec<UniqueVariants>,
+    #[serde(rename = "modTiers", default)]
+    mod_tiers: Vec<ModTier>,
+    #[serde(default)]
+    gems: Vec<Gem>,
+    #[serde(rename = "grantedSkills", default)]
+    granted_skills: Vec<GrantedSkill>,
+}
+
+/// Indexed assets, they are shared by all transformers.
+pub struct AssetsIndex {
+    version: String,
+    unique_variants_idx: HashMap<String, UniqueVariants>,
+    mod_tiers_idx: HashMap<String, Vec<ModTier>>,
+    /// PoB names of gems, supports are named without " Support".
+    gem_names: HashSet<String>,
+    /// Transfigured gems, e.g. "Barrage of Volley Fire".
+    transfigured_gems: HashSet<String>,
+    /// PoB ids of skills granted by items, indexed by En names.
+    granted_skill_ids: HashMap<String, String>,
+}
+
+impl AssetsIndex {
+    fn new(assets: Assets) -> AssetsIndex {
+        let mut unique_variants_idx: HashMap<String, UniqueVariants> = HashMap::new();
+        for v in assets.unique_variants {
+            unique_variants_idx.insert(v.name.clone(), v);
+        }
+
+        let mut mod_tiers_idx: HashMap<String, Vec<ModTier>> = HashMap::new();
+        for t in assets.mod_tiers {
+            mod_tiers_idx
+                .entry(normalize_template(&t.en))
+                .or_default()
+                .push(t);
+        }
+
+        let gem_names: HashSet<String> = assets
+            .gems
+            .iter()
+            .map(|x| x.en.replace(EN_SUPPORT_GEM_SUFFIX, ""))
+            .collect();
+        let transfigured_gems = gem_names
+            .iter()
+            .filter(|x| transfigured_gem_base(&gem_names, x).is_some())
+            .cloned()
+            .collect();
+
+        let granted_skill_ids: HashMap<String, String> = assets
+            .granted_skills
+            .into_iter()
+            .map(|x| (x.en, x.id))
+            .collect();
+
+        AssetsIndex {
+            version: assets.version,
+            unique_variants_idx,
+            mod_tiers_idx,
+            gem_names,
+            transfigured_gems,
+            granted_skill_ids,
+        }
+    }
+}
+
+static ASSETS_INDEX: OnceLock<AssetsIndex> = OnceLock::new();
+
+fn assets_index() -> &'static AssetsIndex {
+    ASSETS_INDEX.get_or_init(|| {
+        let assets: Assets = serde_json::from_str(ASSETS_DATA).unwrap();
+        AssetsIndex::new(assets)
+    })
+}
+
+pub struct Supporter<'a> {
     jewels_meta: Vec<JewelMeta>,
     tree_meta: TreeMeta,
+    assets_index: &'a AssetsIndex,
 }
 
-impl Supporter {
-    pub fn new() -> Supporter {
+impl Supporter<'static> {
+    pub fn new() -> Supporter<'static> {
+        Supporter::with_assets_index(assets_index())
+    }
+}
+
+impl<'a> Supporter<'a> {
+    /// Create a supporter with the given assets, instead of the shared ones.
+    pub fn with_assets_index(assets_index: &'a AssetsIndex) -> Supporter<'a> {
         let jewels_meta: Vec<JewelMeta> = serde_json::from_str(JEWELS_META_DATA).unwrap();
         let tree_meta: TreeMeta = serde_json::from_str(TREE_META_DATA).unwrap();
         Supporter {
             jewels_meta,
             tree_meta,
+            assets_index,
+        }
+    }
+
+    /// The version of assets, empty if it is unknown.
+    pub fn assets_version(&self) -> &str {
+        &self.assets_index.version
+    }
+
+    /// Find the PoB variants of the unique, return the variant names and the index of selected one.
+    pub fn get_unique_variants(&self, name: &str, mods: &[String]) -> Option<(Vec<String>, usize)> {
+        let variants = self.assets_index.unique_variants_idx.get(name)?;
+        let selected = select_unique_variant(variants, mods)?;
+
+        Some((
+            variants.variants.iter().map(|x| x.name.clone()).collect(),
+            selected,
+        ))
+    }
+
+    /// Render the mod with the range of its tier in PoB syntax, e.g. "{range:0.5}+(10-20) to Strength".
+    pub fn get_ranged_mod(&self, mod_str: &str) -> Option<String> {
+        let tiers = self
+            .assets_index
+            .mod_tiers_idx
+            .get(&normalize_mod(mod_str))?;
+        get_ranged_mod(tiers, mod_str)
+    }
+
+    /// Get the gem name recognized by PoB.
+    pub fn get_gem_name(&self, gem: &model::items::Item) -> String {
+        let name = gem.base_type.replace(EN_SUPPORT_GEM_SUFFIX, "");
+
+        if let Some(hybrid) = &gem.hybrid {
+            if let Some(true) = hybrid.is_vaal_gem {
+                let hybrid_name = &hybrid.base_type_name;
+                if self.assets_index.transfigured_gems.contains(hybrid_name)
+                    || is_vaal_transfigured_gem(&name, hybrid_name)
+                {
+                    return format!("{}{}", EN_VAAL_GEM_PREFIX, hybrid_name);
+                }
+            }
+        }
+
+        name
+    }
+
+    /// Whether PoB recognizes the gem name returned by `get_gem_name()`.
+    pub fn is_known_gem(&self, name: &str) -> bool {
+        let index = self.assets_index;
+        if index.gem_names.contains(name) {
+            return true;
         }
+
+        match name.strip_prefix(EN_VAAL_GEM_PREFIX) {
+            Some(hybrid_name) => index.transfigured_gems.contains(hybrid_name),
+            None => false,
+        }
+    }
+
+    /// Get the PoB id of the skill granted by items, e.g. "Summon Bestial Rhoa".
+    pub fn get_granted_skill_id(&self, name: &str) -> Option<&str> {
+        self.assets_index
+            .granted_skill_ids
+            .get(name)
+            .map(|x| x.as_str())
     }
 
     pub fn get_jewel_meta(&self, size: usize) -> &JewelMeta {
@@ -193,8 +354,64 @@ static SLOT_MAP: phf::Map<&'static str, &'static str> = phf_map! {
     "Weapon2" => "Weapon 1 Swap",
 };
 
+/// Skills which are rarely the main skill, e.g. movement skills, auras, heralds and curses.
+static UTILITY_SKILLS: phf::Set<&'static str> = phf_set! {
+    "Arctic Armour", "Blood Rage", "Bodyswap", "Dash", "Flame Dash", "Frostblink", "Leap Slam",
+    "Lightning Warp", "Phase Run", "Shield Charge", "Smoke Mine", "Whirling Blades",
+    "Withering Step", "Blink Arrow", "Mirror Arrow", "Steelskin", "Molten Shell", "Immortal Call",
+    "Vaal Grace", "Haste", "Grace", "Determination", "Discipline", "Purity of Elements",
+    "Purity of Fire", "Purity of Ice", "Purity of Lightning", "Anger", "Hatred", "Wrath", "Zealotry",
+    "Malevolence", "Pride", "Precision", "Clarity", "Vitality", "Defiance Banner", "Dread Banner",
+    "War Banner", "Herald of Ash", "Herald of Ice", "Herald of Thunder", "Herald of Purity",
+    "Herald of Agony", "Enduring Cry", "Rallying Cry", "Ancestral Cry", "Intimidating Cry",
+    "Seismic Cry", "Battlemage's Cry", "Infernal Cry", "General's Cry", "Assassin's Mark",
+    "Poacher's Mark", "Sniper's Mark", "Warlord's Mark", "Despair",
+    "Elemental Weakness", "Enfeeble", "Flammability", "Frostbite", "Conductivity", "Punishment",
+    "Temporal Chains", "Vulnerability", "Portal", "Convocation", "Sigil of Power", "Tempest Shield",
+    "Petrified Blood", "Flesh and Stone", "Summon Skitterbots",
+};
+
+/// Whether the skill is a utility skill, Vaal skills are the same as their base skills.
+pub fn is_utility_skill(name: &str) -> bool {
+    UTILITY_SKILLS.contains(name.strip_prefix(EN_VAAL_GEM_PREFIX).unwrap_or(name))
+}
+
+/// Find skills granted by mods, return the names and levels of skills.
+///
+/// e.g. "Grants Level 20 Summon Bestial Rhoa Skill" and "Trigger Level 20 Bone Nova when you Block".
+pub fn get_granted_skills(mods: &[&String]) -> Vec<(String, i32)> {
+    let patterns = [
+        Regex::new(r"^Grants Level (\d+) (.+) Skill$").unwrap(),
+        Regex::new(r"^(?:\d+% chance to )?Triggers? Level (\d+) (.+?) (?:when|on|after|every) ")
+            .unwrap(),
+    ];
+
+    let mut skills = vec![];
+    for mod_str in mods {
+        for re in &patterns {
+            if let Some(caps) = re.captures(mod_str) {
+                if let Ok(level) = caps[1].parse::<i32>() {
+                    skills.push((caps[2].to_string(), level));
+                }
+                break;
+            }
+        }
+    }
+
+    skills
+}
+
+/// Flasks and tinctures share the flask inventory.
+pub fn is_flask_inventory(inventory_id: &str) -> bool {
+    inventory_id == "Flask"
+}
+
+pub fn is_tincture(base_type: &str) -> bool {
+    base_type.ends_with("Tincture")
+}
+
 pub fn get_slot_name(inventory_id: &str, x: i32) -> String {
-    if inventory_id == "Flask" {
+    if is_flask_inventory(inventory_id) {
         return format!("Flask {}", x + 1);
     }
 
@@ -204,13 +421,344 @@ pub fn get_slot_name(inventory_id: &str, x: i32) -> String {
         .to_string()
 }
 
+const EN_SUPPORT_GEM_SUFFIX: &str = " Support";
+const EN_VAAL_GEM_PREFIX: &str = "Vaal ";
+
+/// Return the base gem of the transfigured gem, e.g. "Rain of Arrows" of "Rain of Arrows of Artillery".
+fn transfigured_gem_base<'a>(gem_names: &HashSet<String>, name: &'a str) -> Option<&'a str> {
+    name.match_indices(" of ")
+        .map(|(i, _)| &name[..i])
+        .find(|x| gem_names.contains(*x))
+}
+
+/// Whether the hybrid skill of the Vaal gem is transfigured, e.g. "Ice Nova of Frostbolts" of "Vaal Ice Nova".
+fn is_vaal_transfigured_gem(vaal_name: &str, hybrid_name: &str) -> bool {
+    match vaal_name.strip_prefix(EN_VAAL_GEM_PREFIX) {
+        Some(base) => hybrid_name
+            .strip_prefix(base)
+            .is_some_and(|x| x.starts_with(" of ")),
+        None => false,
+    }
+}
+
+/// Replace numbers and ranges like "(10-20)" with "#", so mods can be compared regardless of rolls.
+fn normalize_mod(mod_str: &str) -> String {
+    let re = Regex::new(r"\([+-]?\d+(\.\d+)?-[+-]?\d+(\.\d+)?\)|\d+(\.\d+)?").unwrap();
+    re.replace_all(mod_str, "#").into_owned()
+}
+
+/// Normalize the En template of stat like `normalize_mod()`.
+fn normalize_template(tmpl: &str) -> String {
+    let re = Regex::new(r"\{\d+\}").unwrap();
+    normalize_mod(&re.replace_all(tmpl, "0"))
+}
+
+fn fmt_range_number(n: f64) -> String {
+    if n.fract() == 0.0 {
+        format!("{}", n as i64)
+    } else {
+        format!("{}", n)
+    }
+}
+
+/// Find the tier whose ranges contain the values of the mod, and render the mod with the ranges.
+///
+/// PoB accepts one range value per line, it is taken from the first ranged parameter.
+fn get_ranged_mod(tiers: &[ModTier], mod_str: &str) -> Option<String> {
+    for tier in tiers {
+        let tmpl = Template::new(&tier.en);
+        let params = match tmpl.parse_params(mod_str) {
+            Some(params) => params,
+            None => continue,
+        };
+
+        let mut param_nums: Vec<&i32> = params.keys().collect();
+        param_nums.sort();
+
+        let mut ranged_params: HashMap<i32, String> = HashMap::new();
+        let mut range_value: Option<f64> = None;
+        let mut matched = true;
+        for num in param_nums {
+            let value = &params[num];
+            let range = tier.ranges.get(*num as usize);
+            let (range, number) = match (range, value.number()) {
+                (Some(range), Some(number)) => (range, number),
+                _ => {
+                    matched = false;
+                    break;
+                }
+            };
+            if number < range.min || number > range.max {
+                matched = false;
+                break;
+            }
+
+            if range.min == range.max {
+                ranged_params.insert(*num, value.text.clone());
+                continue;
+            }
+
+            if range_value.is_none() {
+                let v = (number - range.min) / (range.max - range.min);
+                range_value = Some((v * 1000.0).round() / 1000.0);
+            }
+            ranged_params.insert(
+                *num,
+                format!(
+                    "({}-{})",
+                    fmt_range_number(range.min),
+                    fmt_range_number(range.max)
+                ),
+            );
+        }
+
+        if !matched {
+            continue;
+        }
+
+        return range_value.map(|v| format!("{{range:{}}}{}", v, tmpl.render(ranged_params)));
+    }
+
+    None
+}
+
+/// Select the variant which has the most mods matched, return the index starts from 0.
+///
+/// The later variant wins when there is a tie, because PoB lists the current variant last.
+fn select_unique_variant(variants: &UniqueVariants, mods: &[String]) -> Option<usize> {
+    let mods: Vec<String> = mods.iter().map(|x| normalize_mod(x)).collect();
+
+    let mut selected: Option<usize> = None;
+    let mut max_score = i32::MIN;
+    for (i, variant) in variants.variants.iter().enumerate() {
+        let matched = variant
+            .mods
+            .iter()
+            .filter(|x| mods.contains(&normalize_mod(x)))
+            .count() as i32;
+        // the mods of variant which the item lacks are penalised
+        let score = matched - (variant.mods.len() as i32 - matched);
+        if matched > 0 && score >= max_score {
+            max_score = score;
+            selected = Some(i);
+        }
+    }
+
+    selected
+}
+
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
 
+    /// Create an assets index from a few assets, other assets are empty.
+    pub(crate) fn new_assets_index(data: serde_json::Value) -> AssetsIndex {
+        let assets: Assets = serde_json::from_value(data).unwrap();
+        AssetsIndex::new(assets)
+    }
+    use crate::db::{ModRange, UniqueVariant};
+
     #[test]
     fn test_deserialize() {
         let _: Vec<JewelMeta> = serde_json::from_str(JEWELS_META_DATA).unwrap();
         let _: TreeMeta = serde_json::from_str(TREE_META_DATA).unwrap();
     }
+
+    #[test]
+    fn test_select_unique_variant() {
+        let variants = UniqueVariants {
+            name: "Shavronne's Wrappings".to_string(),
+            variants: vec![
+                UniqueVariant {
+                    name: "Pre 3.0.0".to_string(),
+                    mods: vec![
+                        "(100-150)% increased Energy Shield".to_string(),
+                        "Reflects 1 to 250 Lightning Damage to Melee Attackers".to_string(),
+                    ],
+                },
+                UniqueVariant {
+                    name: "Current".to_string(),
+                    mods: vec![
+                        "(100-150)% increased Energy Shield".to_string(),
+                        "10% faster start of Energy Shield Recharge".to_string(),
+                    ],
+                },
+            ],
+        };
+
+        let mods = vec![
+            "120% increased Energy Shield".to_string(),
+            "Reflects 1 to 250 Lightning Damage to Melee Attackers".to_string(),
+        ];
+        assert_eq!(select_unique_variant(&variants, &mods), Some(0));
+
+        let mods = vec![
+            "120% increased Energy Shield".to_string(),
+            "10% faster start of Energy Shield Recharge".to_string(),
+        ];
+        assert_eq!(select_unique_variant(&variants, &mods), Some(1));
+
+        let mods = vec!["+10 to Strength".to_string()];
+        assert_eq!(select_unique_variant(&variants, &mods), None);
+
+        let variants = UniqueVariants {
+            name: "Foo".to_string(),
+            variants: vec![
+                UniqueVariant {
+                    name: "Exact".to_string(),
+                    mods: vec![
+                        "+10 to Strength".to_string(),
+                        "+10 to Dexterity".to_string(),
+                    ],
+                },
+                UniqueVariant {
+                    name: "More".to_string(),
+                    mods: vec![
+                        "+10 to Strength".to_string(),
+                        "+10 to Dexterity".to_string(),
+                        "+10 to Intelligence".to_string(),
+                    ],
+                },
+            ],
+        };
+        let mods = vec![
+            "+10 to Strength".to_string(),
+            "+10 to Dexterity".to_string(),
+        ];
+        assert_eq!(select_unique_variant(&variants, &mods), Some(0));
+    }
+
+    #[test]
+    fn test_get_ranged_mod() {
+        let tiers = vec![
+            ModTier {
+                id: "base_maximum_life".to_string(),
+                en: "+{0} to maximum Life".to_string(),
+                tier: 1,
+                ranges: vec![ModRange {
+                    min: 100.0,
+                    max: 109.0,
+                }],
+            },
+            ModTier {
+                id: "base_maximum_life".to_string(),
+                en: "+{0} to maximum Life".to_string(),
+                tier: 2,
+                ranges: vec![ModRange {
+                    min: 90.0,
+                    max: 99.0,
+                }],
+            },
+        ];
+
+        assert_eq!(
+            get_ranged_mod(&tiers, "+94 to maximum Life").unwrap(),
+            "{range:0.444}+(90-99) to maximum Life"
+        );
+        assert_eq!(
+            get_ranged_mod(&tiers, "+100 to maximum Life").unwrap(),
+            "{range:0}+(100-109) to maximum Life"
+        );
+        assert!(get_ranged_mod(&tiers, "+80 to maximum Life").is_none());
+        assert_eq!(
+            normalize_template("+{0} to maximum Life"),
+            normalize_mod("+94 to maximum Life")
+        );
+    }
+
+    #[test]
+    fn test_gem_name() {
+        let index = new_assets_index(serde_json::json!({
+            "gems": [
+                {"zh": "冰霜新星", "en": "Ice Nova"},
+                {"zh": "冰霜新星之冰霜弹", "en": "Ice Nova of Frostbolts"},
+                {"zh": "瓦尔.冰霜新星", "en": "Vaal Ice Nova"},
+                {"zh": "箭雨", "en": "Rain of Arrows"},
+                {"zh": "箭雨之炮击", "en": "Rain of Arrows of Artillery"},
+                {"zh": "多重打击（辅）", "en": "Multistrike Support"},
+            ],
+        }));
+        assert!(index.transfigured_gems.contains("Ice Nova of Frostbolts"));
+        assert!(index
+            .transfigured_gems
+            .contains("Rain of Arrows of Artillery"));
+        assert!(!index.transfigured_gems.contains("Rain of Arrows"));
+
+        let supporter = Supporter::with_assets_index(&index);
+        let new_gem = |base_type: &str, hybrid: Option<&str>| {
+            let mut gem = serde_json::json!({
+                "name": "", "typeLine": base_type, "baseType": base_type, "ilvl": 0, "frameType": 4,
+            });
+            if let Some(hybrid) = hybrid {
+                gem["hybrid"] = serde_json::json!({"baseTypeName": hybrid, "isVaalGem": true});
+            }
+            serde_json::from_value::<model::items::Item>(gem).unwrap()
+        };
+
+        let cases = [
+            (new_gem("Multistrike Support", None), "Multistrike"),
+            (new_gem("Vaal Ice Nova", Some("Ice Nova")), "Vaal Ice Nova"),
+            (
+                new_gem("Vaal Ice Nova", Some("Ice Nova of Frostbolts")),
+                "Vaal Ice Nova of Frostbolts",
+            ),
+        ];
+        for (gem, name) in cases {
+            assert_eq!(supporter.get_gem_name(&gem), name);
+            assert!(supporter.is_known_gem(name));
+        }
+        assert!(!supporter.is_known_gem("Vaal Rain of Arrows"));
+    }
+
+    #[test]
+    fn test_vaal_transfigured_gem() {
+        assert!(is_vaal_transfigured_gem(
+            "Vaal Summon Skeletons",
+            "Summon Skeletons of Mages"
+        ));
+        assert!(!is_vaal_transfigured_gem(
+            "Vaal Summon Skeletons",
+            "Summon Skeletons"
+        ));
+        assert!(!is_vaal_transfigured_gem(
+            "Ice Nova",
+            "Ice Nova of Frostbolts"
+        ));
+    }
+
+    #[test]
+    fn test_get_granted_skills() {
+        let mods: Vec<String> = vec![
+            "Grants Level 20 Summon Bestial Rhoa Skill".to_string(),
+            "+10 to Strength".to_string(),
+            "Trigger Level 20 Bone Nova when you Block".to_string(),
+            "20% chance to Trigger Level 16 Molten Burst on Melee Hit".to_string(),
+        ];
+        let mods: Vec<&String> = mods.iter().collect();
+
+        assert_eq!(
+            get_granted_skills(&mods),
+            vec![
+                ("Summon Bestial Rhoa".to_string(), 20),
+                ("Bone Nova".to_string(), 20),
+                ("Molten Burst".to_string(), 16),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_granted_skill_id() {
+        let index = new_assets_index(serde_json::json!({
+            "grantedSkills": [{"id": "SummonRhoaUniqueBoots", "en": "Summon Bestial Rhoa"}],
+        }));
+        let supporter = Supporter::with_assets_index(&index);
+
+        assert_eq!(
+            supporter.get_granted_skill_id("Summon Bestial Rhoa"),
+            Some("SummonRhoaUniqueBoots")
+        );
+        assert!(supporter
+            .get_granted_skill_id("Summon Bestial Ursa")
+            .is_none());
+    }
 }
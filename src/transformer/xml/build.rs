@@ -4,6 +4,8 @@ pub struct Build {
     pub level: i32,
     pub class_name: String,
     pub ascend_class_name: String,
+    /// The main socket group, starts from 1.
+    pub main_socket_group: usize,
 }
 
 impl Build {}
@@ -14,6 +16,7 @@ impl Default for Build {
             level: 0,
             class_name: String::from("None"),
             ascend_class_name: String::from("None"),
+            main_socket_group: 1,
         }
     }
 }
@@ -22,9 +25,9 @@ impl Display for Build {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            r#"<Build level="{}" className="{}" ascendClassName="{}" targetVersion="3_0" mainSocketGroup="1" viewMode="ITEMS">
+            r#"<Build level="{}" className="{}" ascendClassName="{}" targetVersion="3_0" mainSocketGroup="{}" viewMode="ITEMS">
 </Build>"#,
-            self.level, self.class_name, self.ascend_class_name,
+            self.level, self.class_name, self.ascend_class_name, self.main_socket_group,
         )
     }
 }
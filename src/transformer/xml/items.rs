@@ -2,22 +2,53 @@ use std::{collections::HashMap, fmt::Display};
 
 use phf::phf_map;
 
-use crate::model::{
-    self,
-    items::{ItemProperty, ItemRequirement},
-};
+use crate::model::{self, items::ItemRequirement};
 
-use super::{extract_number, slot::ItemSet};
+use super::{extract_number, slot::ItemSet, PropertyIndex, PropertyKind};
 
 static RARITY_TABLE: [&str; 11] = [
     "NORMAL", "MAGIC", "RARE", "UNIQUE", "", "", "", "", "", "RELIC", "RELIC",
 ];
 
+/// Get the PoB rarity of the frame type, unknown frame types are NORMAL.
+fn get_rarity(frame_type: usize) -> &'static str {
+    match RARITY_TABLE.get(frame_type) {
+        Some(rarity) if !rarity.is_empty() => rarity,
+        _ => RARITY_TABLE[0],
+    }
+}
+
 static ITEM_NAME_MAP: phf::Map<&'static str, &'static str> = phf_map! {
     "Doppelgänger Guise" => "Doppelganger Guise",
     "Mjölner" => "Mjolner",
 };
 
+/// Quality kinds of catalysts, e.g. "Quality (Attack Modifiers)" is Abrasive.
+static CATALYST_TABLE: [(&str, &str); 10] = [
+    ("Attack Modifiers", "Abrasive"),
+    ("Speed Modifiers", "Accelerating"),
+    ("Life and Mana Modifiers", "Fertile"),
+    ("Caster Modifiers", "Imbued"),
+    ("Attribute Modifiers", "Intrinsic"),
+    ("Physical and Chaos Damage Modifiers", "Noxious"),
+    ("Resistance Modifiers", "Prismatic"),
+    ("Defence Modifiers", "Tempering"),
+    ("Elemental Damage Modifiers", "Turbulent"),
+    ("Critical Modifiers", "Unstable"),
+];
+
+/// Get the catalyst of the quality property name, e.g. "Quality (Attack Modifiers)".
+fn get_catalyst(prop_name: &str) -> Option<&'static str> {
+    let kind = prop_name
+        .strip_prefix("Quality (")
+        .and_then(|x| x.strip_suffix(')'))?;
+
+    CATALYST_TABLE
+        .iter()
+        .find(|(k, _)| *k == kind)
+        .map(|(_, v)| *v)
+}
+
 static POB_BASE_TYPE_ENERGY_BLADE: &str = "Energy Blade One Handed";
 
 static BASE_TYPE_MAP: phf::Map<&'static str, &'static str> = phf_map! {
@@ -63,6 +94,8 @@ pub struct Item {
     name: String,
     type_line: String,
     base_type: String,
+    variants: Vec<String>,
+    selected_variant: Option<usize>,
     evasion: Option<String>,
     energy_shield: Option<String>,
     armour: Option<String>,
@@ -78,17 +111,31 @@ pub struct Item {
     tangled: Option<bool>,
     ilvl: i32,
     quality: Option<i32>,
+    catalyst: Option<&'static str>,
     sockets: Option<String>,
     radius: Option<String>,
     limited_to: Option<String>,
     requires_class: Option<String>,
+    level_req: Option<i32>,
+    str_req: Option<i32>,
+    dex_req: Option<i32>,
+    int_req: Option<i32>,
     enchant_mods: Vec<String>,
     implicit_mods: Vec<String>,
     explicit_mods: Vec<String>,
     fractured_mods: Vec<String>,
     crafted_mods: Vec<String>,
     crucible_mods: Vec<String>,
+    scourge_mods: Vec<String>,
+    utility_mods: Vec<String>,
+    mutated_mods: Vec<String>,
     corrupted: bool,
+    mirrored: bool,
+    split: bool,
+    synthesised: bool,
+    fractured: bool,
+    unmodifiable: bool,
+    foil_variation: Option<i32>,
 }
 
 impl Item {
@@ -107,33 +154,22 @@ impl Item {
             None => item.type_line.clone(),
         };
 
-        let mut prop_name_idx: HashMap<&str, &ItemProperty> = HashMap::new();
-        if let Some(props) = &item.properties {
-            for prop in props {
-                prop_name_idx.insert(&prop.name, &prop);
-            }
-        }
-
-        let mut evasion: Option<String> = None;
-        if let Some(prop) = prop_name_idx.get("Evasion Rating") {
-            evasion = Some(prop.values[0].0.clone());
-        }
-        let mut energy_shield: Option<String> = None;
-        if let Some(prop) = prop_name_idx.get("Energy Shield") {
-            energy_shield = Some(prop.values[0].0.clone());
-        }
-        let mut armour: Option<String> = None;
-        if let Some(prop) = prop_name_idx.get("Armour") {
-            armour = Some(prop.values[0].0.clone());
-        }
-        let mut ward: Option<String> = None;
-        if let Some(prop) = prop_name_idx.get("Ward") {
-            ward = Some(prop.values[0].0.clone());
-        }
-        let mut quality: Option<i32> = None;
-        if let Some(prop) = prop_name_idx.get("Quality") {
-            quality = extract_number(&prop.values[0].0);
-        }
+        let props = PropertyIndex::new(item.properties.as_ref());
+
+        let evasion = props
+            .get_value(PropertyKind::EvasionRating)
+            .map(String::from);
+        let energy_shield = props
+            .get_value(PropertyKind::EnergyShield)
+            .map(String::from);
+        let armour = props.get_value(PropertyKind::Armour).map(String::from);
+        let ward = props.get_value(PropertyKind::Ward).map(String::from);
+        let quality = props
+            .get_value(PropertyKind::Quality)
+            .and_then(extract_number);
+        let catalyst = props
+            .get(PropertyKind::Quality)
+            .and_then(|x| get_catalyst(&x.name));
 
         let mut shaper: Option<bool> = None;
         let mut elder: Option<bool> = None;
@@ -176,14 +212,8 @@ impl Item {
             }
         }
 
-        let mut radius: Option<String> = None;
-        if let Some(prop) = prop_name_idx.get("Radius") {
-            radius = Some(prop.values[0].0.clone());
-        }
-        let mut limited_to: Option<String> = None;
-        if let Some(prop) = prop_name_idx.get("Limited to") {
-            limited_to = Some(prop.values[0].0.clone());
-        }
+        let radius = props.get_value(PropertyKind::Radius).map(String::from);
+        let limited_to = props.get_value(PropertyKind::LimitedTo).map(String::from);
 
         let mut req_name_idx: HashMap<&str, &ItemRequirement> = HashMap::new();
         if let Some(req) = &item.requirements {
@@ -196,6 +226,18 @@ impl Item {
         if let Some(req) = req_name_idx.get("Class:") {
             requires_class = Some(req.values[0].0.clone());
         }
+        // suffixes like "(gem)" are dropped, PoB item text has no field for them,
+        // and PoB checks the requirements of socketed gems by the gems themselves
+        let get_req = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|x| req_name_idx.get(x))
+                .and_then(|req| extract_number(&req.values[0].0))
+        };
+        let level_req = get_req(&["Level"]);
+        let str_req = get_req(&["Str", "Strength"]);
+        let dex_req = get_req(&["Dex", "Dexterity"]);
+        let int_req = get_req(&["Int", "Intelligence"]);
 
         let mut enchant_mods: Vec<String> = vec![];
         if let Some(mods) = &item.enchant_mods {
@@ -239,6 +281,29 @@ impl Item {
                 .flat_map(|s| s.lines().map(|x| x.to_string()))
                 .collect();
         }
+        let mut scourge_mods: Vec<String> = vec![];
+        if let Some(mods) = &item.scourge_mods {
+            scourge_mods = mods
+                .iter()
+                .flat_map(|s| s.lines().map(|x| x.to_string()))
+                .collect();
+        }
+        let mut utility_mods: Vec<String> = vec![];
+        if let Some(mods) = &item.utility_mods {
+            utility_mods = mods
+                .iter()
+                .flat_map(|s| s.lines().map(|x| x.to_string()))
+                .collect();
+        }
+        // veiled mods are skipped, they are ids without text and PoB can't calculate them;
+        // memory strands are skipped too, PoB has no item text for them
+        let mut mutated_mods: Vec<String> = vec![];
+        if let Some(mods) = &item.mutated_mods {
+            mutated_mods = mods
+                .iter()
+                .flat_map(|s| s.lines().map(|x| x.to_string()))
+                .collect();
+        }
 
         if item.base_type == POB_BASE_TYPE_ENERGY_BLADE {
             implicit_mods = vec![];
@@ -248,13 +313,20 @@ impl Item {
         }
 
         let corrupted = item.corrupted.unwrap_or(false);
+        let mirrored = item.duplicated.unwrap_or(false);
+        let split = item.split.unwrap_or(false);
+        let synthesised = item.synthesised.unwrap_or(false);
+        let fractured = item.fractured.unwrap_or(false);
+        let unmodifiable = item.unmodifiable.unwrap_or(false);
 
         Item {
             id,
-            rarity: RARITY_TABLE[item.frame_type].to_string(),
+            rarity: get_rarity(item.frame_type).to_string(),
             name,
             type_line,
             base_type,
+            variants: vec![],
+            selected_variant: None,
             evasion,
             energy_shield,
             armour,
@@ -270,21 +342,75 @@ impl Item {
             tangled,
             ilvl: item.ilvl,
             quality,
+            catalyst,
             sockets: pob_sockets,
             radius,
             limited_to,
             requires_class,
+            level_req,
+            str_req,
+            dex_req,
+            int_req,
             enchant_mods,
             implicit_mods,
             explicit_mods,
             fractured_mods,
             crafted_mods,
             crucible_mods,
+            scourge_mods,
+            utility_mods,
+            mutated_mods,
             corrupted,
+            mirrored,
+            split,
+            synthesised,
+            fractured,
+            unmodifiable,
+            foil_variation: item.foil_variation,
         }
     }
 }
 
+impl Item {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The full name like PoB, e.g. "Bisco's Leash, Heavy Belt", or the type line of unnamed items.
+    pub fn full_name(&self) -> String {
+        if self.name.is_empty() {
+            self.type_line.clone()
+        } else {
+            format!("{}, {}", self.name, self.base_type)
+        }
+    }
+
+    pub fn is_unique(&self) -> bool {
+        self.rarity == "UNIQUE" || self.rarity == "RELIC"
+    }
+
+    /// Return implicit, explicit, fractured and crafted mods.
+    pub fn mods(&self) -> Vec<String> {
+        self.implicit_mods
+            .iter()
+            .chain(self.explicit_mods.iter())
+            .chain(self.fractured_mods.iter())
+            .chain(self.crafted_mods.iter())
+            .cloned()
+            .collect()
+    }
+
+    pub fn explicit_mods_mut(&mut self) -> &mut Vec<String> {
+        &mut self.explicit_mods
+    }
+
+    /// Set the PoB variants of unique, `selected` starts from 0.
+    pub fn set_variants(&mut self, variants: Vec<String>, selected: usize) {
+        self.variants = variants;
+        self.selected_variant = Some(selected);
+    }
+}
+
 impl Display for Item {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut builder: Vec<String> = vec![];
@@ -300,6 +426,13 @@ impl Display for Item {
             builder.push(self.base_type.clone());
         }
 
+        for variant in &self.variants {
+            builder.push(format!("Variant: {}", variant));
+        }
+        if let Some(selected) = self.selected_variant {
+            builder.push(format!("Selected Variant: {}", selected + 1));
+        }
+
         if let Some(evasion) = &self.evasion {
             builder.push(format!("Evasion: {}", evasion));
         }
@@ -340,9 +473,24 @@ impl Display for Item {
         if let Some(true) = self.tangled {
             builder.push("Eater of Worlds Item".to_string());
         }
+        if self.synthesised {
+            builder.push("Synthesised Item".to_string());
+        }
+        if self.fractured {
+            builder.push("Fractured Item".to_string());
+        }
+        if let Some(foil_variation) = self.foil_variation {
+            builder.push(format!("Foil Variation: {}", foil_variation));
+        }
         builder.push(format!("Item Level: {}", self.ilvl));
         if let Some(quality) = self.quality {
-            builder.push(format!("Quality: {}", quality));
+            match self.catalyst {
+                Some(catalyst) => {
+                    builder.push(format!("Catalyst: {}", catalyst));
+                    builder.push(format!("CatalystQuality: {}", quality));
+                }
+                None => builder.push(format!("Quality: {}", quality)),
+            }
         }
         if let Some(sockets) = &self.sockets {
             builder.push(format!("Sockets: {}", sockets));
@@ -360,21 +508,47 @@ impl Display for Item {
             builder.push(format!("Requires Class {}", requires_class));
         }
 
-        let implicit_count = self.enchant_mods.len() + self.implicit_mods.len();
+        if let Some(level_req) = self.level_req {
+            builder.push(format!("LevelReq: {}", level_req));
+        }
+        if let Some(str_req) = self.str_req {
+            builder.push(format!("Str: {}", str_req));
+        }
+        if let Some(dex_req) = self.dex_req {
+            builder.push(format!("Dex: {}", dex_req));
+        }
+        if let Some(int_req) = self.int_req {
+            builder.push(format!("Int: {}", int_req));
+        }
+
+        let implicit_count =
+            self.enchant_mods.len() + self.scourge_mods.len() + self.implicit_mods.len();
         builder.push(format!("Implicits: {}", implicit_count));
 
         for mod_str in &self.enchant_mods {
             builder.push(format!("{{crafted}}{}", mod_str));
         }
 
+        for mod_str in &self.scourge_mods {
+            builder.push(format!("{{scourge}}{}", mod_str));
+        }
+
         for mod_str in &self.implicit_mods {
             builder.push(mod_str.to_string());
         }
 
+        for mod_str in &self.utility_mods {
+            builder.push(mod_str.to_string());
+        }
+
         for mod_str in &self.explicit_mods {
             builder.push(mod_str.to_string());
         }
 
+        for mod_str in &self.mutated_mods {
+            builder.push(format!("{{mutated}}{}", mod_str));
+        }
+
         for mod_str in &self.fractured_mods {
             builder.push(format!("{{fractured}}{}", mod_str));
         }
@@ -390,6 +564,15 @@ impl Display for Item {
         if self.corrupted {
             builder.push("Corrupted".to_string());
         }
+        if self.mirrored {
+            builder.push("Mirrored".to_string());
+        }
+        if self.split {
+            builder.push("Split".to_string());
+        }
+        if self.unmodifiable {
+            builder.push("Unmodifiable".to_string());
+        }
 
         write!(
             f,
@@ -401,3 +584,194 @@ impl Display for Item {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::{get_catalyst, get_rarity, Item};
+    use crate::model;
+
+    /// Create a normal Onyx Amulet, the fields are overridden by `extra`.
+    fn item(extra: Value) -> model::items::Item {
+        let mut data = json!({
+            "id": "abc",
+            "name": "",
+            "typeLine": "Onyx Amulet",
+            "baseType": "Onyx Amulet",
+            "ilvl": 86,
+            "frameType": 0,
+        });
+        for (k, v) in extra.as_object().unwrap() {
+            data[k] = v.clone();
+        }
+
+        serde_json::from_value(data).unwrap()
+    }
+
+    #[test]
+    fn test_requirements() {
+        let item = item(json!({
+            "requirements": [
+                {"name": "Level", "values": [["68", 0]], "displayMode": 0},
+                {"name": "Int", "values": [["194", 0]], "displayMode": 1},
+            ],
+        }));
+
+        let s = Item::new(1, &item).to_string();
+        assert!(s.contains("\nLevelReq: 68\n"));
+        assert!(s.contains("\nInt: 194\n"));
+        assert!(!s.contains("Str:"));
+    }
+
+    #[test]
+    fn test_typed_properties() {
+        let item = item(json!({
+            "typeLine": "Vaal Regalia",
+            "baseType": "Vaal Regalia",
+            "properties": [
+                {"name": "品质", "values": [["+20%", 1]], "displayMode": 0, "type": 6},
+                {"name": "能量护盾", "values": [["300", 1]], "displayMode": 0, "type": 18},
+                {"name": "Ward", "values": [["10", 1]], "displayMode": 0},
+            ],
+        }));
+
+        let s = Item::new(1, &item).to_string();
+        assert!(s.contains("\nQuality: 20\n"));
+        assert!(s.contains("\nEnergy Shield: 300\n"));
+        assert!(s.contains("\nWard: 10\n"));
+    }
+
+    #[test]
+    fn test_catalyst() {
+        assert_eq!(get_catalyst("Quality (Attack Modifiers)"), Some("Abrasive"));
+        assert_eq!(
+            get_catalyst("Quality (Physical and Chaos Damage Modifiers)"),
+            Some("Noxious")
+        );
+        assert_eq!(get_catalyst("Quality"), None);
+
+        let item = item(json!({
+            "properties": [
+                {"name": "Quality (Life and Mana Modifiers)", "values": [["+20%", 1]], "displayMode": 0},
+            ],
+        }));
+
+        let s = Item::new(1, &item).to_string();
+        assert!(s.contains("\nCatalyst: Fertile\nCatalystQuality: 20\n"));
+        assert!(!s.contains("\nQuality:"));
+    }
+
+    #[test]
+    fn test_flags() {
+        assert_eq!(get_rarity(3), "UNIQUE");
+        assert_eq!(get_rarity(10), "RELIC");
+        assert_eq!(get_rarity(4), "NORMAL");
+        assert_eq!(get_rarity(12), "NORMAL");
+
+        let item = item(json!({
+            "frameType": 12,
+            "duplicated": true,
+            "split": true,
+            "synthesised": true,
+            "fractured": true,
+        }));
+
+        let s = Item::new(1, &item).to_string();
+        assert!(s.contains("Rarity: NORMAL\n"));
+        for flag in ["Mirrored", "Split", "Synthesised Item", "Fractured Item"] {
+            assert!(s.contains(&format!("\n{}\n", flag)));
+        }
+    }
+
+    #[test]
+    fn test_unmodifiable() {
+        let item = item(json!({"unmodifiable": true}));
+
+        let s = Item::new(1, &item).to_string();
+        assert!(s.contains("\nUnmodifiable\n"));
+    }
+
+    #[test]
+    fn test_foil_variation() {
+        let mut item = item(json!({
+            "name": "Headhunter",
+            "typeLine": "Leather Belt",
+            "baseType": "Leather Belt",
+            "frameType": 10,
+            "foilVariation": 3,
+        }));
+
+        let s = Item::new(1, &item).to_string();
+        assert!(s.contains("Rarity: RELIC\n"));
+        assert!(s.contains("\nFoil Variation: 3\n"));
+
+        item.foil_variation = None;
+        assert!(!Item::new(1, &item).to_string().contains("Foil"));
+    }
+
+    #[test]
+    fn test_flask() {
+        let item = item(json!({
+            "typeLine": "Silver Flask",
+            "baseType": "Silver Flask",
+            "properties": [
+                {"name": "Quality", "values": [["+20%", 1]], "displayMode": 0, "type": 6},
+                {"name": "Lasts %0 Seconds", "values": [["6.00", 1]], "displayMode": 3},
+            ],
+            "enchantMods": ["23% increased effect", "Gains no Charges during Effect"],
+        }));
+
+        // charges and duration are calculated by PoB, the enchants of enkindling orbs are kept
+        let s = Item::new(1, &item).to_string();
+        assert!(s.contains("\nQuality: 20\n"));
+        assert!(!s.contains("Lasts"));
+        assert!(s.contains(
+            "Implicits: 2\n{crafted}23% increased effect\n{crafted}Gains no Charges during Effect\n"
+        ));
+    }
+
+    #[test]
+    fn test_full_name() {
+        let unique = item(json!({
+            "name": "Bisco's Leash",
+            "typeLine": "Heavy Belt",
+            "baseType": "Heavy Belt",
+            "frameType": 3,
+        }));
+        assert_eq!(
+            Item::new(1, &unique).full_name(),
+            "Bisco's Leash, Heavy Belt"
+        );
+
+        let magic = item(json!({
+            "typeLine": "Experimenter's Heavy Belt",
+            "baseType": "Heavy Belt",
+            "frameType": 1,
+        }));
+        assert_eq!(
+            Item::new(1, &magic).full_name(),
+            "Experimenter's Heavy Belt"
+        );
+    }
+
+    #[test]
+    fn test_mods() {
+        let item = item(json!({
+            "frameType": 2,
+            "implicitMods": ["+16 to all Attributes"],
+            "scourgeMods": ["+20 to maximum Life"],
+            "explicitMods": ["+30 to Strength"],
+            "mutatedMods": ["10% increased Attack Speed"],
+            "veiledMods": ["PrefixVeiled01"],
+            "memoryStrands": 30,
+        }));
+        assert_eq!(item.memory_strands, Some(30));
+
+        let s = Item::new(1, &item).to_string();
+        assert!(s.contains(
+            "Implicits: 2\n{scourge}+20 to maximum Life\n+16 to all Attributes\n+30 to Strength\n{mutated}10% increased Attack Speed\n"
+        ));
+        assert!(!s.contains("Veiled"));
+    }
+}
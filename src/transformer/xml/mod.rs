@@ -1,15 +1,136 @@
+use std::collections::HashMap;
+
 use regex::Regex;
 
+use crate::model::items::ItemProperty;
+
 pub mod build;
 pub mod config;
 pub mod items;
+pub mod notes;
 pub mod path_of_building;
 pub mod skills;
 pub mod slot;
 pub mod tree;
 
+/// Properties used by PoB.
+#[derive(Clone, Copy)]
+pub enum PropertyKind {
+    Level,
+    Quality,
+    Armour,
+    EvasionRating,
+    EnergyShield,
+    Ward,
+    Radius,
+    LimitedTo,
+}
+
+impl PropertyKind {
+    /// The `type` of property in the GGG item JSON, only for the confirmed ones.
+    fn type_v(&self) -> Option<i32> {
+        match self {
+            Self::Level => Some(5),
+            Self::Quality => Some(6),
+            Self::Armour => Some(16),
+            Self::EvasionRating => Some(17),
+            Self::EnergyShield => Some(18),
+            _ => None,
+        }
+    }
+
+    /// The En name, it is used when the property has no `type`.
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Level => "Level",
+            Self::Quality => "Quality",
+            Self::Armour => "Armour",
+            Self::EvasionRating => "Evasion Rating",
+            Self::EnergyShield => "Energy Shield",
+            Self::Ward => "Ward",
+            Self::Radius => "Radius",
+            Self::LimitedTo => "Limited to",
+        }
+    }
+}
+
+/// Find properties by `type` first, then by En name, so untranslated properties can be found.
+pub struct PropertyIndex<'a> {
+    props: &'a [ItemProperty],
+    type_idx: HashMap<i32, &'a ItemProperty>,
+    name_idx: HashMap<&'a str, &'a ItemProperty>,
+}
+
+impl<'a> PropertyIndex<'a> {
+    pub fn new(props: Option<&'a Vec<ItemProperty>>) -> PropertyIndex<'a> {
+        let mut type_idx: HashMap<i32, &ItemProperty> = HashMap::new();
+        let mut name_idx: HashMap<&str, &ItemProperty> = HashMap::new();
+        let props = props.map_or(&[][..], |x| x.as_slice());
+        for prop in props {
+            if let Some(type_v) = prop.type_v {
+                type_idx.insert(type_v, prop);
+            }
+            name_idx.insert(&prop.name, prop);
+        }
+
+        PropertyIndex {
+            props,
+            type_idx,
+            name_idx,
+        }
+    }
+
+    pub fn get(&self, kind: PropertyKind) -> Option<&'a ItemProperty> {
+        if let Some(prop) = kind.type_v().and_then(|x| self.type_idx.get(&x)) {
+            return Some(prop);
+        }
+
+        let name = kind.name();
+        if let Some(prop) = self.name_idx.get(name) {
+            return Some(prop);
+        }
+
+        // the quality of catalysts, e.g. "Quality (Attack Modifiers)"
+        match kind {
+            PropertyKind::Quality => self.props.iter().find(|x| {
+                x.name
+                    .strip_prefix(name)
+                    .is_some_and(|x| x.starts_with(" ("))
+            }),
+            _ => None,
+        }
+    }
+
+    /// Get the first value of the property.
+    pub fn get_value(&self, kind: PropertyKind) -> Option<&'a str> {
+        self.get(kind)
+            .and_then(|x| x.values.first())
+            .map(|x| x.0.as_str())
+    }
+}
+
 pub fn extract_number(s: &str) -> Option<i32> {
     let re = Regex::new(r"\d+").unwrap(); // 匹配一个或多个数字
     let caps = re.captures(s)?;
     caps[0].parse::<i32>().ok()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_property_index() {
+        let props: Vec<ItemProperty> = serde_json::from_value(serde_json::json!([
+            {"name": "Radius (Large)", "values": [["Large", 0]], "displayMode": 0},
+            {"name": "Quality (Attack Modifiers)", "values": [["+10%", 1]], "displayMode": 0},
+            {"name": "Quality (Speed Modifiers)", "values": [["+20%", 1]], "displayMode": 0},
+        ]))
+        .unwrap();
+        let index = PropertyIndex::new(Some(&props));
+
+        // only quality has kinds, the first one is used
+        assert!(index.get(PropertyKind::Radius).is_none());
+        assert_eq!(index.get_value(PropertyKind::Quality), Some("+10%"));
+    }
+}
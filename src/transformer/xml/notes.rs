@@ -0,0 +1,94 @@
+use std::fmt::Display;
+
+/// Where the build comes from and what might be wrong with it.
+pub struct Notes {
+    pub character_name: String,
+    pub league: String,
+    pub realm: String,
+    pub level: i32,
+    /// The unix time of exporting.
+    pub export_time: Option<u64>,
+    pub assets_version: String,
+    pub untranslated: Vec<String>,
+}
+
+impl Default for Notes {
+    fn default() -> Self {
+        Self {
+            character_name: "".to_string(),
+            league: "".to_string(),
+            realm: "".to_string(),
+            level: 0,
+            export_time: None,
+            assets_version: "".to_string(),
+            untranslated: vec![],
+        }
+    }
+}
+
+impl Display for Notes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut builder: Vec<String> = vec![];
+        builder.push(format!("Character: {}", self.character_name));
+        builder.push(format!("League: {}", self.league));
+        builder.push(format!("Realm: {}", self.realm));
+        builder.push(format!("Level: {}", self.level));
+        if let Some(time) = self.export_time {
+            builder.push(format!("Exported at: {} (unix time)", time));
+        }
+        if !self.assets_version.is_empty() {
+            builder.push(format!("Assets version: {}", self.assets_version));
+        }
+        if !self.untranslated.is_empty() {
+            builder.push(format!("Untranslated: {}", self.untranslated.len()));
+            for s in &self.untranslated {
+                builder.push(format!("- {}", s));
+            }
+        }
+
+        write!(f, "<Notes>\n{}\n</Notes>", escape(&builder.join("\n")))
+    }
+}
+
+/// Notes are free text, untranslated strings may contain markup characters.
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Notes;
+
+    #[test]
+    fn test_notes() {
+        let notes = Notes {
+            character_name: "Foo".to_string(),
+            league: "Standard".to_string(),
+            realm: "pc".to_string(),
+            level: 95,
+            export_time: Some(1700000000),
+            assets_version: "3.27.0".to_string(),
+            untranslated: vec!["explicit mod, 最大生命 <10>".to_string()],
+        };
+
+        assert_eq!(
+            notes.to_string(),
+            "<Notes>
+Character: Foo
+League: Standard
+Realm: pc
+Level: 95
+Exported at: 1700000000 (unix time)
+Assets version: 3.27.0
+Untranslated: 1
+- explicit mod, 最大生命 &lt;10&gt;
+</Notes>"
+        );
+
+        let notes = Notes::default().to_string();
+        assert!(!notes.contains("Exported at"));
+        assert!(!notes.contains("Untranslated"));
+    }
+}
@@ -1,6 +1,6 @@
 use std::fmt::Display;
 
-use super::{build::Build, config::Config, items::Items, skills::Skills, tree::Tree};
+use super::{build::Build, config::Config, items::Items, notes::Notes, skills::Skills, tree::Tree};
 
 pub struct PathOfBuilding {
     pub build: Build,
@@ -8,6 +8,7 @@ pub struct PathOfBuilding {
     pub tree: Tree,
     pub items: Items,
     pub config: Config,
+    pub notes: Notes,
 }
 
 impl Default for PathOfBuilding {
@@ -17,6 +18,7 @@ impl Default for PathOfBuilding {
         let tree = Tree::default();
         let items = Items::default();
         let config = Config::default();
+        let notes = Notes::default();
 
         PathOfBuilding {
             build,
@@ -24,6 +26,7 @@ impl Default for PathOfBuilding {
             tree,
             items,
             config,
+            notes,
         }
     }
 }
@@ -39,8 +42,9 @@ impl Display for PathOfBuilding {
 {}
 {}
 {}
+{}
 </PathOfBuilding>"#,
-            self.build, self.skills, self.tree, self.items, self.config
+            self.build, self.notes, self.skills, self.tree, self.items, self.config
         )
     }
 }
@@ -1,198 +1,8 @@
-use std::{collections::HashMap, fmt::Display};
-
-use phf::phf_set;
-
-use crate::model::{self, items::ItemProperty};
-
-use super::extract_number;
-
-static TRANSFIGURED_GEMS: phf::Set<&'static str> = phf_set! {
-    "Ice Nova of Frostbolts",
-    "Ice Nova of Deep Freeze",
-    "Leap Slam of Groundbreaking",
-    "Blade Flurry of Incision",
-    "Ground Slam of Earthshaking",
-    "Cleave of Rage",
-    "Double Strike of Impaling",
-    "Double Strike of Momentum",
-    "Lacerate of Butchering",
-    "Lacerate of Haemorrhage",
-    "Elemental Hit of the Spectrum",
-    "Dual Strike of Ambidexterity",
-    "Frenzy of Onslaught",
-    "Detonate Dead of Scavenging",
-    "Detonate Dead of Chain Reaction",
-    "Volatile Dead of Confinement",
-    "Volatile Dead of Seething",
-    "Split Arrow of Splitting",
-    "Cold Snap of Power",
-    "Raise Zombie of Slamming",
-    "Raise Zombie of Falling",
-    "Caustic Arrow of Poison",
-    "Righteous Fire of Arcane Devotion",
-    "Discharge of Misery",
-    "Flicker Strike of Power",
-    "Spark of the Nova",
-    "Spark of Unpredictability",
-    "Ice Spear of Splitting",
-    "Raise Spectre of Transience",
-    "Infernal Blow of Immolation",
-    "Glacial Hammer of Shattering",
-    "Viper Strike of the Mamba",
-    "Summon Skeletons of Archers",
-    "Summon Skeletons of Mages",
-    "Dominating Blow of Inspiring",
-    "Rain of Arrows of Artillery",
-    "Rain of Arrows of Saturation",
-    "Firestorm of Meteors",
-    "Firestorm of Pelting",
-    "Lightning Strike of Arcing",
-    "Power Siphon of the Archmage",
-    "Puncture of Shanking",
-    "Lightning Arrow of Electrocution",
-    "Burning Arrow of Vigour",
-    "Icicle Mine of Fanning",
-    "Icicle Mine of Sabotage",
-    "Bear Trap of Skewers",
-    "Fire Trap of Blasting",
-    "Ethereal Knives of Lingering Blades",
-    "Ethereal Knives of the Massacre",
-    "Ice Shot of Penetration",
-    "Arc of Surging",
-    "Arc of Oscillating",
-    "Holy Flame Totem of Ire",
-    "Incinerate of Expanse",
-    "Incinerate of Venting",
-    "Cyclone of Tumult",
-    "Reave of Refraction",
-    "Lightning Trap of Sparking",
-    "Animate Guardian of Smiting",
-    "Spectral Shield Throw of Shattering",
-    "Spectral Throw of Materialising",
-    "Animate Weapon of Self Reflection",
-    "Animate Weapon of Ranged Arms",
-    "Flameblast of Celerity",
-    "Flameblast of Contraction",
-    "Barrage of Volley Fire",
-    "Ball Lightning of Orbiting",
-    "Ball Lightning of Static",
-    "Summon Raging Spirit of Enormity",
-    "Flame Surge of Combusting",
-    "Glacial Cascade of the Fissure",
-    "Molten Strike of the Zenith",
-    "Pyroclast Mine of Sabotage",
-    "Tornado Shot of Cloudburst",
-    "Lightning Tendrils of Eccentricity",
-    "Lightning Tendrils of Escalation",
-    "Kinetic Blast of Clustering",
-    "Blink Arrow of Bombarding Clones",
-    "Blink Arrow of Prismatic Clones",
-    "Mirror Arrow of Bombarding Clones",
-    "Mirror Arrow of Prismatic Clones",
-    "Summon Chaos Golem of Hordes",
-    "Summon Chaos Golem of the Maelstr枚m",
-    "Summon Ice Golem of Hordes",
-    "Summon Ice Golem of Shattering",
-    "Summon Flame Golem of Hordes",
-    "Summon Flame Golem of the Meteor",
-    "Summon Lightning Golem of Hordes",
-    "Ice Crash of Cadence",
-    "Flame Dash of Return",
-    "Frost Blades of Katabasis",
-    "Wild Strike of Extremes",
-    "Ice Trap of Hollowness",
-    "Galvanic Arrow of Energy",
-    "Galvanic Arrow of Surging",
-    "Bladefall of Volleys",
-    "Bladefall of Impaling",
-    "Blade Vortex of the Scythe",
-    "Frost Bomb of Instability",
-    "Frost Bomb of Forthcoming",
-    "Siege Ballista of Splintering",
-    "Summon Stone Golem of Hordes",
-    "Summon Stone Golem of Safeguarding",
-    "Earthquake of Amplification",
-    "Contagion of Subsiding",
-    "Contagion of Transference",
-    "Blight of Contagion",
-    "Blight of Atrophy",
-    "Essence Drain of Desperation",
-    "Essence Drain of Wickedness",
-    "Sunder of Earthbreaking",
-    "Vortex of Projection",
-    "Scorching Ray of Immolation",
-    "Cremation of Exhuming",
-    "Cremation of the Volcano",
-    "Bodyswap of Sacrifice",
-    "Tectonic Slam of Cataclysm",
-    "Explosive Trap of Shrapnel",
-    "Explosive Trap of Magnitude",
-    "Lightning Spire Trap of Zapping",
-    "Lightning Spire Trap of Overloading",
-    "Seismic Trap of Swells",
-    "Consecrated Path of Endurance",
-    "Smite of Divine Judgement",
-    "Scourge Arrow of Menace",
-    "Toxic Rain of Sporeburst",
-    "Toxic Rain of Withering",
-    "Summon Holy Relic of Conviction",
-    "Lancing Steel of Spraying",
-    "Storm Brand of Indecision",
-    "Shattering Steel of Ammunition",
-    "Armageddon Brand of Volatility",
-    "Armageddon Brand of Recall",
-    "Purifying Flame of Revelations",
-    "Soulrend of Reaping",
-    "Soulrend of the Spiral",
-    "Bane of Condemnation",
-    "Divine Ire of Holy Lightning",
-    "Divine Ire of Disintegration",
-    "Bladestorm of Uncertainty",
-    "Perforate of Duality",
-    "Perforate of Bloodshed",
-    "Frostblink of Wintry Blast",
-    "Summon Carrion Golem of Hordes",
-    "Summon Carrion Golem of Scavenging",
-    "Shrapnel Ballista of Steel",
-    "Artillery Ballista of Cross Strafe",
-    "Artillery Ballista of Focus Fire",
-    "Stormbind of Teleportation",
-    "Kinetic Bolt of Fragmentation",
-    "Blade Blast of Unloading",
-    "Blade Blast of Dagger Detonation",
-    "Penance Brand of Dissipation",
-    "Penance Brand of Conduction",
-    "Earthshatter of Fragility",
-    "Earthshatter of Prominence",
-    "Void Sphere of Rending",
-    "Crackling Lance of Branching",
-    "Crackling Lance of Disintegration",
-    "Splitting Steel of Ammunition",
-    "Hexblast of Contradiction",
-    "Hexblast of Havoc",
-    "Exsanguinate of Transmission",
-    "Rage Vortex of Berserking",
-    "Absolution of Inspiring",
-    "Forbidden Rite of Soul Sacrifice",
-    "Blade Trap of Greatswords",
-    "Blade Trap of Laceration",
-    "Storm Rain of the Conduit",
-    "Storm Rain of the Fence",
-    "Shield Crush of the Chieftain",
-    "Summon Reaper of Revenants",
-    "Summon Reaper of Eviscerating",
-    "Boneshatter of Complex Trauma",
-    "Boneshatter of Carnage",
-    "Explosive Concoction of Destruction",
-    "Eye of Winter of Finality",
-    "Eye of Winter of Transience",
-    "Poisonous Concoction of Bouncing",
-    "Tornado of Elemental Turbulence",
-    "Lightning Conduit of the Heavens",
-    "Galvanic Field of Intensity",
-    "Volcanic Fissure of Snaking",
-    "Frozen Legion of Rallying",
-};
+use std::fmt::Display;
+
+use crate::model;
+
+use super::{extract_number, PropertyIndex, PropertyKind};
 
 pub struct Skills {
     pub skill_set: SkillSet,
@@ -247,6 +57,10 @@ impl Display for SkillSet {
 pub struct Skill {
     slot: String,
     pub gems: Vec<Gem>,
+    /// The main active skill, starts from 1 and counts active skills of gems, a Vaal gem has two.
+    main_active_skill: Option<usize>,
+    /// The source of item-granted skills, e.g. "Item:3:Bisco's Leash, Heavy Belt".
+    source: Option<String>,
 }
 
 impl Skill {
@@ -256,18 +70,41 @@ impl Skill {
         Skill {
             slot: slot_name.to_string(),
             gems: gem_list,
+            main_active_skill: None,
+            source: None,
         }
     }
+
+    /// Create a skill group granted by the item.
+    pub fn new_granted(slot_name: &str, source: &str) -> Skill {
+        let mut skill = Self::new(slot_name);
+        skill.source = Some(source.to_string());
+
+        skill
+    }
+
+    pub fn slot(&self) -> &str {
+        &self.slot
+    }
+
+    pub fn set_main_active_skill(&mut self, index: usize) {
+        self.main_active_skill = Some(index);
+    }
 }
 
 impl Display for Skill {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            r#"<Skill enabled="true" slot="{}" mainActiveSkill="nil">
+            r#"<Skill enabled="true" slot="{}"{} mainActiveSkill="{}">
 {}
 </Skill>"#,
             self.slot,
+            self.source
+                .as_ref()
+                .map_or("".to_string(), |x| format!(r#" source="{}""#, x)),
+            self.main_active_skill
+                .map_or("nil".to_string(), |x| x.to_string()),
             self.gems
                 .iter()
                 .map(|x| x.to_string())
@@ -278,10 +115,13 @@ impl Display for Skill {
 }
 
 pub struct Gem {
+    support: bool,
     level: i32,
     quality_id: String,
     quality: i32,
     name_spec: String,
+    /// The PoB id of the skill, it is required by skills which are not gems.
+    skill_id: Option<String>,
     enable_global1: bool,
     enable_global2: bool,
 }
@@ -289,10 +129,12 @@ pub struct Gem {
 impl Default for Gem {
     fn default() -> Self {
         Self {
+            support: false,
             level: 20,
             quality_id: "Default".to_string(),
             quality: 0,
             name_spec: "".to_string(),
+            skill_id: None,
             enable_global1: true,
             enable_global2: false,
         }
@@ -300,38 +142,44 @@ impl Default for Gem {
 }
 
 impl Gem {
-    fn is_vaal_gem(&self) -> bool {
+    pub fn is_vaal_gem(&self) -> bool {
         self.name_spec.starts_with("Vaal ")
     }
 
-    pub fn new(data: &model::items::Item) -> Gem {
-        let mut gem = Self::default();
+    pub fn is_support(&self) -> bool {
+        self.support
+    }
 
-        let mut prop_name_idx: HashMap<&str, &ItemProperty> = HashMap::new();
-        if let Some(props) = &data.properties {
-            for prop in props {
-                prop_name_idx.insert(&prop.name, &prop);
-            }
-        }
+    pub fn level(&self) -> i32 {
+        self.level
+    }
 
-        if let Some(prop) = prop_name_idx.get("Level") {
-            gem.level = extract_number(&prop.values[0].0).unwrap();
-        }
-        if let Some(prop) = prop_name_idx.get("Quality") {
-            gem.quality = extract_number(&prop.values[0].0).unwrap();
-        }
+    pub fn name(&self) -> &str {
+        &self.name_spec
+    }
 
-        gem.name_spec = data.base_type.replace(" Support", "");
+    /// Create a gem, `name_spec` is the name recognized by PoB.
+    pub fn new(data: &model::items::Item, name_spec: String) -> Gem {
+        let mut gem = Self::default();
 
-        if let Some(hybrid) = &data.hybrid {
-            if let Some(true) = hybrid.is_vaal_gem {
-                let hybrid_base_type_name = &hybrid.base_type_name;
-                if TRANSFIGURED_GEMS.contains(hybrid_base_type_name.as_str()) {
-                    gem.name_spec = format!("Vaal {}", hybrid_base_type_name);
-                }
-            }
+        let props = PropertyIndex::new(data.properties.as_ref());
+        if let Some(level) = props
+            .get_value(PropertyKind::Level)
+            .and_then(extract_number)
+        {
+            gem.level = level;
+        }
+        if let Some(quality) = props
+            .get_value(PropertyKind::Quality)
+            .and_then(extract_number)
+        {
+            gem.quality = quality;
         }
 
+        // requirements of gems are not kept, PoB derives them from the gem level
+        gem.support = data.base_type.ends_with(" Support");
+        gem.name_spec = name_spec;
+
         if gem.is_vaal_gem() {
             gem.enable_global1 = false;
             gem.enable_global2 = true;
@@ -339,29 +187,54 @@ impl Gem {
 
         gem
     }
+
+    /// Create the gem of the skill granted by the item, `skill_id` is the PoB id of the skill.
+    pub fn new_granted(name: &str, level: i32, skill_id: Option<&str>) -> Gem {
+        Gem {
+            name_spec: name.to_string(),
+            level,
+            skill_id: skill_id.map(String::from),
+            ..Default::default()
+        }
+    }
 }
 
 impl Display for Gem {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            r#"<Gem level="{}" qualityId="{}" quality="{}" nameSpec="{}" enabled="true" enableGlobal1="{}" enableGlobal2="{}"/>"#,
+            r#"<Gem level="{}" qualityId="{}" quality="{}" nameSpec="{}"{} enabled="true" enableGlobal1="{}" enableGlobal2="{}"/>"#,
             self.level,
             self.quality_id,
             self.quality,
             self.name_spec,
+            self.skill_id
+                .as_ref()
+                .map_or("".to_string(), |x| format!(r#" skillId="{}""#, x)),
             self.enable_global1,
             self.enable_global2
         )
     }
 }
 
+#[cfg(test)]
 mod tests {
-    #[test]
-    fn test_transfigured_gems() {
-        use super::TRANSFIGURED_GEMS;
+    use super::{Gem, Skill};
 
-        let name = format!("{} of {}", "Barrage", "Volley Fire");
-        assert!(TRANSFIGURED_GEMS.contains(&name));
+    #[test]
+    fn test_granted_skill() {
+        let mut skill = Skill::new_granted("Boots", "Item:3:Ghostwrithe, Silk Slippers");
+        skill.gems.push(Gem::new_granted(
+            "Summon Bestial Rhoa",
+            20,
+            Some("SummonRhoaUniqueBoots"),
+        ));
+
+        let s = skill.to_string();
+        assert!(s.contains(r#"source="Item:3:Ghostwrithe, Silk Slippers""#));
+        assert!(s.contains(r#"nameSpec="Summon Bestial Rhoa" skillId="SummonRhoaUniqueBoots""#));
+
+        let gem = Gem::new_granted("Bone Nova", 20, None).to_string();
+        assert!(!gem.contains("skillId"));
     }
 }
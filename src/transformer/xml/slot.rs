@@ -62,16 +62,22 @@ impl Slot {
         slot.name = name.to_string();
         slot.item_id = Some(item_id);
 
-        if slot.name.starts_with("Flask ") {
-            slot.active = true;
-        }
+        slot
+    }
+
+    /// Create a flask slot, tinctures are in flask slots too.
+    pub fn new_flask(name: &str, item_id: i32, active: bool) -> Slot {
+        let mut slot = Self::new_equipment(name, item_id);
+        slot.active = active;
 
         slot
     }
 
-    pub fn new_jewel(name: &str, node_id: i32) -> Slot {
+    /// Create a slot of the jewel socket in the passive tree, cluster jewel sockets included.
+    pub fn new_jewel(node_id: i32, item_id: i32) -> Slot {
         let mut slot = Self::default();
-        slot.name = name.to_string();
+        slot.name = jewel_slot_name(node_id);
+        slot.item_id = Some(item_id);
         slot.node_id = Some(node_id);
 
         slot
@@ -118,6 +124,16 @@ pub struct SocketIdURL {
     item_pb_url: String,
 }
 
+impl SocketIdURL {
+    pub fn new(node_id: i32) -> SocketIdURL {
+        SocketIdURL {
+            node_id,
+            name: jewel_slot_name(node_id),
+            ..Default::default()
+        }
+    }
+}
+
 impl Default for SocketIdURL {
     fn default() -> Self {
         Self {
@@ -137,3 +153,38 @@ impl Display for SocketIdURL {
         )
     }
 }
+
+fn jewel_slot_name(node_id: i32) -> String {
+    format!("Jewel {}", node_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Slot, SocketIdURL};
+
+    #[test]
+    fn test_flask_slot() {
+        let slot = Slot::new_flask("Flask 1", 3, true);
+        assert!(slot.to_string().contains(r#"active="true""#));
+
+        let slot = Slot::new_flask("Flask 2", 4, false);
+        assert!(!slot.to_string().contains("active"));
+
+        let slot = Slot::new_equipment("Helmet", 5);
+        assert!(!slot.to_string().contains("active"));
+    }
+
+    #[test]
+    fn test_jewel_slot() {
+        let slot = Slot::new_jewel(26725, 7).to_string();
+        assert!(slot.contains(r#"name="Jewel 26725""#));
+        assert!(slot.contains(r#"itemId="7""#));
+        assert!(slot.contains(r#"nodeId="26725""#));
+
+        let url = SocketIdURL::new(26725).to_string();
+        assert_eq!(
+            url,
+            r#"<SocketIdURL nodeId="26725" name="Jewel 26725" itemPbURL=""/>"#
+        );
+    }
+}
@@ -189,24 +189,61 @@ impl Display for Overrides {
 pub struct Override {
     pub dn: String,
     pub node_id: i32,
+    /// The mod lines of the override, e.g. stats of the tattoo.
+    pub stats: Vec<String>,
 }
 
 impl Override {
-    pub fn new(dn: &str, node_id: i32) -> Override {
+    pub fn new(dn: &str, node_id: i32, stats: Vec<String>) -> Override {
         Override {
             dn: dn.to_string(),
             node_id,
+            stats,
         }
     }
 }
 
 impl Display for Override {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let stats_view = self
+            .stats
+            .iter()
+            .map(|x| format!("{}\n", x))
+            .collect::<Vec<_>>()
+            .join("");
+
         write!(
             f,
             r#"<Override dn="{}" nodeId="{}">
-</Override>"#,
-            self.dn, self.node_id,
+{}</Override>"#,
+            self.dn, self.node_id, stats_view,
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Override;
+
+    #[test]
+    fn test_override() {
+        let o = Override::new(
+            "Tattoo of the Hinekora Storyteller",
+            1234,
+            vec!["+2% to Critical Strike Multiplier".to_string()],
+        );
+        assert_eq!(
+            o.to_string(),
+            r#"<Override dn="Tattoo of the Hinekora Storyteller" nodeId="1234">
++2% to Critical Strike Multiplier
+</Override>"#
+        );
+
+        let o = Override::new("Ghost Dance", 1234, vec![]);
+        assert_eq!(
+            o.to_string(),
+            r#"<Override dn="Ghost Dance" nodeId="1234">
+</Override>"#
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+use crate::db::Affix;
+
+/// Prefixes and suffixes indexed by zh, for magic affixes or rare name parts.
+pub struct Provider {
+    prefixes_zh_idx: HashMap<String, Affix>,
+    suffixes_zh_idx: HashMap<String, Affix>,
+}
+
+impl Provider {
+    pub fn new(prefixes: Vec<Affix>, suffixes: Vec<Affix>) -> Provider {
+        let mut prefixes_zh_idx: HashMap<String, Affix> = HashMap::new();
+        let mut suffixes_zh_idx: HashMap<String, Affix> = HashMap::new();
+
+        for a in prefixes {
+            prefixes_zh_idx.insert(a.zh.clone(), a);
+        }
+
+        for a in suffixes {
+            suffixes_zh_idx.insert(a.zh.clone(), a);
+        }
+
+        Provider {
+            prefixes_zh_idx,
+            suffixes_zh_idx,
+        }
+    }
+
+    pub fn provide_prefix_by_zh(&self, zh: &str) -> Option<&Affix> {
+        self.prefixes_zh_idx.get(zh)
+    }
+
+    pub fn provide_suffix_by_zh(&self, zh: &str) -> Option<&Affix> {
+        self.suffixes_zh_idx.get(zh)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Provider;
+    use crate::db::Affix;
+
+    #[test]
+    fn test_new() {
+        let prefixes = vec![Affix {
+            zh: String::from("实验者的"),
+            en: String::from("Experimenter's"),
+        }];
+        let suffixes = vec![Affix {
+            zh: String::from("彩虹之"),
+            en: String::from("of the Rainbow"),
+        }];
+
+        let provider = Provider::new(prefixes, suffixes);
+
+        assert_eq!(
+            provider.provide_prefix_by_zh("实验者的").unwrap().en,
+            "Experimenter's"
+        );
+        assert_eq!(
+            provider.provide_suffix_by_zh("彩虹之").unwrap().en,
+            "of the Rainbow"
+        );
+        assert!(provider.provide_prefix_by_zh("彩虹之").is_none());
+    }
+}
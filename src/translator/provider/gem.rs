@@ -4,12 +4,18 @@ use crate::db::{Gem, Skill};
 
 pub struct Provider {
     zh_idx: HashMap<String, Skill>,
+    descr_zh_idx: HashMap<String, String>,
 }
 
 impl Provider {
     pub fn new(gems: Vec<Gem>, hybrid_skills: Vec<Skill>) -> Provider {
         let mut zh_idx: HashMap<String, Skill> = HashMap::new();
+        let mut descr_zh_idx: HashMap<String, String> = HashMap::new();
         for gem in gems {
+            if let (Some(descr_zh), Some(descr_en)) = (gem.descr_zh, gem.descr_en) {
+                descr_zh_idx.insert(descr_zh, descr_en);
+            }
+
             zh_idx.insert(
                 gem.zh.clone(),
                 Skill {
@@ -23,10 +29,18 @@ impl Provider {
             zh_idx.insert(skill.zh.clone(), skill);
         }
 
-        Provider { zh_idx }
+        Provider {
+            zh_idx,
+            descr_zh_idx,
+        }
     }
 
     pub fn provide_by_zh(&self, zh: &str) -> Option<&Skill> {
         self.zh_idx.get(zh)
     }
+
+    /// Provide the En description of gems by the zh one.
+    pub fn provide_descr_by_zh(&self, zh: &str) -> Option<&String> {
+        self.descr_zh_idx.get(zh)
+    }
 }
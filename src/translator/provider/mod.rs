@@ -1,3 +1,4 @@
+pub mod affix;
 pub mod attribute;
 pub mod base_type;
 pub mod gem;
@@ -1,45 +1,92 @@
+use std::collections::HashMap;
+
 use regex::Regex;
 
 use crate::{
-    db::{BaseType, Stat},
+    db::{Affix, BaseType, Stat},
     translator::{
-        provider::{attribute, base_type, gem, passive_skill, property, requirement, stat},
-        util::{get_zh_body, stat::Template, LINE_SEPARATOR},
+        provider::{affix, attribute, base_type, gem, passive_skill, property, requirement, stat},
+        util::{
+            get_zh_body,
+            stat::{Param, Template},
+            LINE_SEPARATOR,
+        },
     },
 };
 
+use super::rule::{self, ModRule, Rules};
+
+/// The mod resolved by `Basic::resolve_mod()`.
+pub struct ResolvedMod<'a> {
+    /// The matched stat.
+    pub stat: &'a Stat,
+    /// The GGG stat ids of the matched stat.
+    pub ids: &'a [String],
+    /// The typed positional parameters parsed from the mod.
+    pub params: HashMap<i32, Param>,
+    /// The En translation.
+    pub en: String,
+    /// Whether the mod is the sign-flipped variant of the stat, e.g. "降低" of a "提高" stat.
+    ///
+    /// The parameters are what the mod shows, the actual values are negative.
+    pub sign_flipped: bool,
+}
+
+/// A candidate stat of the mod, see `Basic::rank_mod_candidates()`.
+pub struct StatCandidate<'a> {
+    pub stat: &'a Stat,
+    /// The specificity of the stat's template, 0 if the template can't parse the mod.
+    pub score: usize,
+    /// The resolved mod, None if the template can't parse the mod.
+    pub resolved: Option<ResolvedMod<'a>>,
+}
+
 /// Basic translator supports basic services.
 pub struct Basic {
+    affix_provider: affix::Provider,
     attribute_provider: attribute::Provider,
     basetype_provider: base_type::Provider,
     gem_provider: gem::Provider,
     passive_skill_provider: passive_skill::Provider,
     property_provider: property::Provider,
+    rare_name_provider: affix::Provider,
     requirement_provider: requirement::Provider,
     stat_provider: stat::Provider,
+    mod_rules: Rules<dyn ModRule>,
 }
 
 impl Basic {
     /// Create a new basic translator.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
+        affix_provider: affix::Provider,
         attribute_provider: attribute::Provider,
         basetype_provider: base_type::Provider,
         gem_provider: gem::Provider,
         passive_skill_provider: passive_skill::Provider,
         property_provider: property::Provider,
+        rare_name_provider: affix::Provider,
         requirement_provider: requirement::Provider,
         stat_provider: stat::Provider,
     ) -> Basic {
         Basic {
+            affix_provider,
             attribute_provider,
             basetype_provider,
             gem_provider,
             passive_skill_provider,
             property_provider,
+            rare_name_provider,
             requirement_provider,
             stat_provider,
+            mod_rules: rule::default_mod_rules(),
         }
     }
+
+    /// The rules of special mods, rules can be registered, enabled or disabled.
+    pub fn mod_rules_mut(&mut self) -> &mut Rules<dyn ModRule> {
+        &mut self.mod_rules
+    }
 }
 
 /// methods for base type
@@ -98,14 +145,7 @@ impl Basic {
     /// For magic items, the type line contains adjectives like "模范的炉火之".
     /// The method will remove the adjectives and find the base type.
     fn find_base_type_by_type_line(&self, type_line: &str) -> Option<&BaseType> {
-        let mut type_line = type_line;
-        if type_line.starts_with(Self::ZH_SUPERIOR_PREFIX) {
-            type_line = &type_line[Self::ZH_SUPERIOR_PREFIX.len()..];
-        }
-
-        if type_line.starts_with(Self::ZH_SYNTHESISED_PREFIX) {
-            type_line = &type_line[Self::ZH_SYNTHESISED_PREFIX.len()..];
-        }
+        let type_line = Self::trim_type_line_prefixes(type_line);
 
         let mut result = self.find_base_type(type_line);
         if result.is_some() {
@@ -125,11 +165,76 @@ impl Basic {
         result
     }
 
+    /// Remove the "精良的 " and "忆境 " prefixes of the type line.
+    fn trim_type_line_prefixes(type_line: &str) -> &str {
+        let mut type_line = type_line;
+        if type_line.starts_with(Self::ZH_SUPERIOR_PREFIX) {
+            type_line = &type_line[Self::ZH_SUPERIOR_PREFIX.len()..];
+        }
+
+        if type_line.starts_with(Self::ZH_SYNTHESISED_PREFIX) {
+            type_line = &type_line[Self::ZH_SYNTHESISED_PREFIX.len()..];
+        }
+
+        type_line
+    }
+
+    /// Find the prefix and suffix which make up the affix part of a magic type line.
+    ///
+    /// The prefix comes first in Zh, e.g. "实验者的彩虹之" is "实验者的" + "彩虹之".
+    fn find_magic_affixes(&self, affixes: &str) -> Option<(Option<&Affix>, Option<&Affix>)> {
+        for i in (0..=affixes.len()).filter(|i| affixes.is_char_boundary(*i)) {
+            let (prefix, suffix) = affixes.split_at(i);
+
+            let prefix = match prefix {
+                "" => None,
+                _ => match self.affix_provider.provide_prefix_by_zh(prefix) {
+                    Some(a) => Some(a),
+                    None => continue,
+                },
+            };
+            let suffix = match suffix {
+                "" => None,
+                _ => match self.affix_provider.provide_suffix_by_zh(suffix) {
+                    Some(a) => Some(a),
+                    None => continue,
+                },
+            };
+
+            return Some((prefix, suffix));
+        }
+
+        None
+    }
+
+    /// Translate the name of rare items.
+    ///
+    /// The name is made up of a prefix and a suffix, e.g. "灾难之握" is "灾难" + "之握".
+    /// Return the En name like "Doom Grasp".
+    pub fn trans_rare_name(&self, name: &str) -> Option<String> {
+        for i in (1..name.len()).filter(|i| name.is_char_boundary(*i)) {
+            let (prefix, suffix) = name.split_at(i);
+            let prefix = self
+                .rare_name_provider
+                .provide_prefix_by_zh(prefix.trim_end());
+            let suffix = self
+                .rare_name_provider
+                .provide_suffix_by_zh(suffix.trim_start());
+
+            if let (Some(prefix), Some(suffix)) = (prefix, suffix) {
+                return Some(format!("{} {}", prefix.en, suffix.en));
+            }
+        }
+
+        None
+    }
+
     /// Translate the name and base type.
     ///
     /// If there is a unique with the same name and base type, return the unique's En name and En base type.
-    /// Otherwise, translate the base type and return (default name, base_type) if input name is not empty
+    /// Otherwise, translate the base type and return (name, base_type) if input name is not empty
     /// or ("", base_type) if input name is empty.
+    /// The name is translated as a rare name, the default name is used if it fails.
     pub fn trans_name_and_base_type(
         &self,
         name: &str,
@@ -153,7 +258,9 @@ impl Basic {
             return Some((
                 match name.len() {
                     0 => String::from(""),
-                    _ => String::from(Self::EN_DEFALUT_NAME),
+                    _ => self
+                        .trans_rare_name(name)
+                        .unwrap_or(String::from(Self::EN_DEFALUT_NAME)),
                 },
                 en,
             ));
@@ -171,6 +278,39 @@ impl Basic {
         }
     }
 
+    /// Translate the type line of magic items.
+    ///
+    /// The type line is made up of the prefix name, the suffix name and the base type,
+    /// e.g. "实验者的彩虹之钻石药剂".
+    /// The method returns the full En name like "Experimenter's Diamond Flask of the Rainbow".
+    pub fn trans_magic_type_line(&self, type_line: &str) -> Option<String> {
+        let type_line = Self::trim_type_line_prefixes(type_line);
+
+        let re = Regex::new(r".+?[之的]").unwrap();
+        for m in re.find_iter(type_line) {
+            let b = self.find_base_type(&type_line[m.end()..]);
+            if b.is_none() {
+                continue;
+            }
+            let b = b.unwrap();
+
+            if let Some((prefix, suffix)) = self.find_magic_affixes(&type_line[..m.end()]) {
+                let mut parts: Vec<&str> = vec![];
+                if let Some(prefix) = prefix {
+                    parts.push(&prefix.en);
+                }
+                parts.push(&b.en);
+                if let Some(suffix) = suffix {
+                    parts.push(&suffix.en);
+                }
+
+                return Some(parts.join(" "));
+            }
+        }
+
+        None
+    }
+
     /// Translate the type line.
     ///
     /// Find the base type by type line and return the En base type.
@@ -184,6 +324,8 @@ impl Basic {
 }
 
 static GEM_PROPERTY_NAMES: [(&str, &str); 2] = [("等级", "Level"), ("品质", "Quality")];
+static ZH_GEM_TAG_SEPARATORS: [char; 2] = [',', '，'];
+static EN_GEM_TAG_SEPARATOR: &str = ", ";
 
 /// methods for attribute,gem,prop,requirements...
 impl Basic {
@@ -232,6 +374,12 @@ impl Basic {
         None
     }
 
+    /// Translate the gem description, i.e. `secDescrText`.
+    pub fn trans_gem_descr(&self, zh: &str) -> Option<String> {
+        self.gem_provider.provide_descr_by_zh(zh).cloned()
+    }
+
+    /// Translate the gem property name, e.g. "等级", "施放时间" or the tag line "攻击, 近战".
     pub fn trans_gem_prop(&self, zh: &str) -> Option<String> {
         for property in GEM_PROPERTY_NAMES {
             if property.0 == zh {
@@ -239,7 +387,34 @@ impl Basic {
             }
         }
 
-        None
+        if let Some(en) = self.trans_prop_name(zh) {
+            return Some(en);
+        }
+
+        self.trans_gem_tags(zh)
+    }
+
+    fn trans_gem_tags(&self, zh: &str) -> Option<String> {
+        if !zh.contains(ZH_GEM_TAG_SEPARATORS) {
+            return None;
+        }
+
+        zh.split(ZH_GEM_TAG_SEPARATORS)
+            .map(|x| self.trans_prop_name(x.trim()))
+            .collect::<Option<Vec<_>>>()
+            .map(|x| x.join(EN_GEM_TAG_SEPARATOR))
+    }
+
+    /// Translate the gem property value, e.g. "10 魔力", numeric values are not translated.
+    pub fn trans_gem_prop_value(&self, zh_name: &str, value: &str) -> Option<String> {
+        if let (_, Some(en)) = self.trans_prop(zh_name, value) {
+            return Some(en);
+        }
+        if value.is_ascii() {
+            return None;
+        }
+
+        self.trans_prop_name(value)
     }
 
     pub fn trans_notable(&self, zh: &str) -> Option<String> {
@@ -299,7 +474,7 @@ impl Basic {
         if let Some(props) = props {
             for prop in props {
                 let zh_tmpl = Template::new(&prop.zh);
-                let params = zh_tmpl.parse_params(name);
+                let params = zh_tmpl.parse_text_params(name);
                 if params.is_none() {
                     continue;
                 }
@@ -348,148 +523,129 @@ impl Basic {
     }
 }
 
+static ZH_SIGN_FLIPPED_WORDS: [(&str, &str); 2] = [("降低", "提高"), ("减少", "增加")];
+static EN_SIGN_FLIPPED_WORDS: [(&str, &str); 2] = [("increased", "reduced"), ("more", "less")];
+
 /// methods for stat
 impl Basic {
-    const ZH_ANOINTED_MOD_PREFIX: &str = "配置 ";
-    const ZH_FORBIDDEN_FLAME_MOD_PREFIX: &str = "禁断之火上有匹配的词缀则配置 ";
-    const ZH_FORBIDDEN_FLESH_MOD_PREFIX: &str = "禁断之肉上有匹配的词缀则配置 ";
-
-    const ZH_UNIQUE_ENEMY_IN_YOUR_PRESENCE: &str = "有一个传奇怪物出现在你面前：";
-    const EN_UNIQUE_ENEMY_IN_YOUR_PRESENCE: &str = "While a Unique Enemy is in your Presence, ";
-    const ZH_PINNACLE_ATLAS_BOSS_IN_YOUR_PRESENCE: &str = "有一个异界图鉴最终首领出现在你面前：";
-    const EN_PINNACLE_ATLAS_BOSS_IN_YOUR_PRESENCE: &str =
-        "While a Pinnacle Atlas Boss is in your Presence, ";
-
-    fn is_anointed_mod(&self, mod_str: &str) -> bool {
-        mod_str.starts_with(Self::ZH_ANOINTED_MOD_PREFIX)
-    }
-
-    fn trans_anointed_mod(&self, mod_str: &str) -> Option<String> {
-        let notable = &mod_str[Self::ZH_ANOINTED_MOD_PREFIX.len()..];
-        let trans = self.trans_notable(notable);
-
-        match trans {
-            Some(trans) => Some(format!("Allocates {}", trans)),
-            None => None,
+    /// Translate the mod, special mods are handled by mod rules before stats.
+    pub fn trans_mod(&self, mod_str: &str) -> Option<String> {
+        // the mod falls back to stats if no rule translates it
+        for rule in self.mod_rules.iter() {
+            if let Some(trans) = rule.trans_mod(self, mod_str) {
+                return Some(trans);
+            }
         }
-    }
 
-    fn is_forbidden_flame_mod(&self, mod_str: &str) -> bool {
-        mod_str.starts_with(Self::ZH_FORBIDDEN_FLAME_MOD_PREFIX)
-    }
-
-    fn trans_forbidden_flame_mod(&self, mod_str: &str) -> Option<String> {
-        let ascendant = &mod_str[Self::ZH_FORBIDDEN_FLAME_MOD_PREFIX.len()..];
-        let trans = self.trans_ascendant(ascendant);
-
-        match trans {
-            Some(trans) => Some(format!(
-                "Allocates {} if you have the matching modifier on Forbidden Flame",
-                trans
-            )),
-            None => None,
-        }
+        self.trans_mod_inner(mod_str)
     }
 
-    fn is_forbidden_flesh_mod(&self, mod_str: &str) -> bool {
-        mod_str.starts_with(Self::ZH_FORBIDDEN_FLESH_MOD_PREFIX)
+    fn trans_mod_inner(&self, mod_str: &str) -> Option<String> {
+        self.resolve_mod(mod_str).map(|x| x.en)
     }
 
-    fn trans_forbidden_flesh_mod(&self, mod_str: &str) -> Option<String> {
-        let ascendant = &mod_str[Self::ZH_FORBIDDEN_FLESH_MOD_PREFIX.len()..];
-        let trans = self.trans_ascendant(ascendant);
-
-        match trans {
-            Some(trans) => Some(format!(
-                "Allocates {} if you have the matching modifier on Forbidden Flesh",
-                trans
-            )),
-            None => None,
+    /// Resolve the mod by stats, return the matched stat, its ids and the parameters.
+    ///
+    /// Mods wrapped by prefixes like "配置 " are not resolved, use `trans_mod()` for them.
+    pub fn resolve_mod(&self, mod_str: &str) -> Option<ResolvedMod<'_>> {
+        let result = self
+            .rank_mod_candidates(mod_str)
+            .into_iter()
+            .find_map(|x| x.resolved);
+        if result.is_some() {
+            return result;
         }
-    }
 
-    fn is_eldritch_implicit_mod(&self, mod_str: &str) -> bool {
-        mod_str.starts_with(Self::ZH_UNIQUE_ENEMY_IN_YOUR_PRESENCE)
-            || mod_str.starts_with(Self::ZH_PINNACLE_ATLAS_BOSS_IN_YOUR_PRESENCE)
+        self.resolve_sign_flipped_mod(mod_str)
     }
 
-    fn trans_eldritch_implicit_mod(&self, mod_str: &str) -> Option<String> {
-        if mod_str.starts_with(Self::ZH_UNIQUE_ENEMY_IN_YOUR_PRESENCE) {
-            let sub_mod = &mod_str[Self::ZH_UNIQUE_ENEMY_IN_YOUR_PRESENCE.len()..];
-            let trans = self.trans_mod(sub_mod);
-            match trans {
-                Some(trans) => {
-                    return Some(format!(
-                        "{}{}",
-                        Self::EN_UNIQUE_ENEMY_IN_YOUR_PRESENCE,
-                        &trans
-                    ))
-                }
-                None => return None,
+    /// Resolve the mod with "降低" or "减少" wording by the matching "提高" or "增加" stat,
+    /// the En is rendered with "reduced" or "less".
+    fn resolve_sign_flipped_mod(&self, mod_str: &str) -> Option<ResolvedMod<'_>> {
+        for (zh_flipped, zh) in ZH_SIGN_FLIPPED_WORDS {
+            if !mod_str.contains(zh_flipped) {
+                continue;
             }
-        }
 
-        if mod_str.starts_with(Self::ZH_PINNACLE_ATLAS_BOSS_IN_YOUR_PRESENCE) {
-            let sub_mod = &mod_str[Self::ZH_PINNACLE_ATLAS_BOSS_IN_YOUR_PRESENCE.len()..];
-            let trans = self.trans_mod(sub_mod);
-            match trans {
-                Some(trans) => {
-                    return Some(format!(
-                        "{}{}",
-                        Self::EN_PINNACLE_ATLAS_BOSS_IN_YOUR_PRESENCE,
-                        &trans
-                    ))
+            let flipped = mod_str.replace(zh_flipped, zh);
+            let resolved = self
+                .rank_mod_candidates(&flipped)
+                .into_iter()
+                .find_map(|x| x.resolved);
+            if let Some(mut resolved) = resolved {
+                let mut en_flipped = false;
+                for (en, en_reversed) in EN_SIGN_FLIPPED_WORDS {
+                    let re = Regex::new(&format!(r"\b{}\b", en)).unwrap();
+                    if re.is_match(&resolved.en) {
+                        resolved.en = re.replace_all(&resolved.en, en_reversed).into_owned();
+                        en_flipped = true;
+                    }
+                }
+
+                if en_flipped {
+                    resolved.sign_flipped = true;
+                    return Some(resolved);
                 }
-                None => return None,
             }
         }
 
         None
     }
 
-    pub fn trans_mod(&self, mod_str: &str) -> Option<String> {
-        if self.is_anointed_mod(mod_str) {
-            return self.trans_anointed_mod(mod_str);
-        }
-        if self.is_forbidden_flame_mod(mod_str) {
-            return self.trans_forbidden_flame_mod(mod_str);
-        }
-        if self.is_forbidden_flesh_mod(mod_str) {
-            return self.trans_forbidden_flesh_mod(mod_str);
-        }
-        if self.is_eldritch_implicit_mod(mod_str) {
-            return self.trans_eldritch_implicit_mod(mod_str);
-        }
-
-        self.trans_mod_inner(mod_str)
-    }
-
-    fn trans_mod_inner(&self, mod_str: &str) -> Option<String> {
+    /// Return all stats sharing the Zh body with the mod, ranked by their scores.
+    ///
+    /// A stat with more literal characters and fixed numbers matched scores higher,
+    /// the first resolved candidate is used for translation.
+    pub fn rank_mod_candidates(&self, mod_str: &str) -> Vec<StatCandidate<'_>> {
         let body = get_zh_body(mod_str);
         let stats = self.stat_provider.provide_by_zh(&body);
-
-        if let Some(stats) = stats {
-            for stat in stats {
-                let result = self.do_trans_mod(stat, mod_str);
-                if result.is_some() {
-                    return result;
+        if stats.is_none() {
+            return vec![];
+        }
+
+        let mut candidates: Vec<StatCandidate> = stats
+            .unwrap()
+            .into_iter()
+            .map(|stat| {
+                let resolved = self.do_resolve_mod(stat, mod_str);
+                let score = match resolved {
+                    Some(_) => Template::new(&stat.zh).specificity(),
+                    None => 0,
+                };
+                StatCandidate {
+                    stat,
+                    score,
+                    resolved,
                 }
-            }
-        }
+            })
+            .collect();
+        candidates.sort_by(|a, b| a.score.cmp(&b.score).reverse());
 
-        None
+        candidates
     }
 
-    fn do_trans_mod(&self, stat: &Stat, mod_str: &str) -> Option<String> {
+    fn do_resolve_mod<'a>(&self, stat: &'a Stat, mod_str: &str) -> Option<ResolvedMod<'a>> {
         if mod_str == stat.zh {
-            return Some(stat.en.clone());
+            return Some(ResolvedMod {
+                stat,
+                ids: &stat.ids,
+                params: HashMap::new(),
+                en: stat.en.clone(),
+                sign_flipped: false,
+            });
         }
 
         let zh_tmpl = Template::new(&stat.zh);
         let params = zh_tmpl.parse_params(mod_str);
         if let Some(params) = params {
             let en_tmpl = Template::new(&stat.en);
-            return Some(en_tmpl.render(params));
+            return Some(ResolvedMod {
+                stat,
+                ids: &stat.ids,
+                en: en_tmpl.render_params(&params),
+                params,
+                sign_flipped: false,
+            });
         }
 
         None
@@ -522,9 +678,9 @@ impl Basic {
 
                 let stat = self.stat_provider.provide(m_stat.id);
                 if get_zh_body(&mod_str) == get_zh_body(&stat.zh) {
-                    let result = self.do_trans_mod(stat, &mod_str);
-                    if result.is_some() {
-                        return result;
+                    let result = self.do_resolve_mod(stat, &mod_str);
+                    if let Some(result) = result {
+                        return Some(result.en);
                     }
                 }
             }
@@ -541,6 +697,7 @@ fn test_translate() {
     let contents = assets::ASSETS_DATA;
     let assets: Assets = serde_json::from_str(&contents).unwrap();
 
+    let affix_provider = affix::Provider::new(assets.prefixes, assets.suffixes);
     let attribute_provider = attribute::Provider::new(assets.attributes);
     let basetype_provider = base_type::Provider::new(vec![
         assets.amulets,
@@ -557,20 +714,24 @@ fn test_translate() {
         assets.tinctures,
         assets.weapons,
         assets.tattoos,
+        assets.grafts,
     ]);
     let gem_provider = gem::Provider::new(assets.gems, assets.hybrid_skills);
     let passive_skill_provider =
         passive_skill::Provider::new(assets.notables, assets.keystones, assets.ascendant);
     let property_provider = property::Provider::new(assets.properties);
+    let rare_name_provider = affix::Provider::new(assets.rare_prefixes, assets.rare_suffixes);
     let requirement_provider =
         requirement::Provider::new(assets.requirements, assets.requirement_suffixes);
     let stat_provider = stat::Provider::new(assets.stats);
     let basic = Basic::new(
+        affix_provider,
         attribute_provider,
         basetype_provider,
         gem_provider,
         passive_skill_provider,
         property_provider,
+        rare_name_provider,
         requirement_provider,
         stat_provider,
     );
@@ -583,3 +744,179 @@ fn test_translate() {
     assert!(&basic.trans_gem("投射物归返（辅）").unwrap() == "Returning Projectiles Support");
     assert!(&basic.trans_gem("增幅(辅)").unwrap() == "Enhance Support");
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use serde_json::{json, Value};
+
+    use super::Basic;
+    use crate::{db::Assets, translator::translator::zh_to_en::Factory};
+
+    /// Create a basic translator from a few assets, other assets are empty.
+    pub(crate) fn new_basic(data: Value) -> std::sync::Arc<Basic> {
+        let mut assets = json!({
+            "amulets": [], "belts": [], "rings": [], "bodyArmours": [], "boots": [],
+            "gloves": [], "helmets": [], "quivers": [], "shields": [], "weapons": [],
+            "flasks": [], "jewels": [], "tinctures": [], "gems": [], "hybridSkills": [],
+            "attributes": [], "properties": [], "requirements": [], "requirementSuffixes": [],
+            "ascendant": [], "keystones": [], "notables": [], "stats": [], "tattoos": [],
+            "grafts": [],
+        });
+        for (k, v) in data.as_object().unwrap() {
+            assets[k] = v.clone();
+        }
+
+        let assets: Assets = serde_json::from_value(assets).unwrap();
+        Factory::from_assets(assets).basic_translator()
+    }
+
+    #[test]
+    fn test_trans_magic_type_line() {
+        let basic = new_basic(json!({
+            "flasks": [{"zh": "钻石药剂", "en": "Diamond Flask"}],
+            "prefixes": [{"zh": "实验者的", "en": "Experimenter's"}],
+            "suffixes": [{"zh": "彩虹之", "en": "of the Rainbow"}],
+        }));
+
+        assert_eq!(
+            basic
+                .trans_magic_type_line("实验者的彩虹之钻石药剂")
+                .unwrap(),
+            "Experimenter's Diamond Flask of the Rainbow"
+        );
+        assert_eq!(
+            basic.trans_magic_type_line("实验者的钻石药剂").unwrap(),
+            "Experimenter's Diamond Flask"
+        );
+        assert_eq!(
+            basic.trans_magic_type_line("彩虹之钻石药剂").unwrap(),
+            "Diamond Flask of the Rainbow"
+        );
+        assert!(basic.trans_magic_type_line("未知的钻石药剂").is_none());
+        assert_eq!(
+            basic.trans_type_line("未知的钻石药剂").unwrap(),
+            "Diamond Flask"
+        );
+    }
+
+    #[test]
+    fn test_trans_rare_name() {
+        let basic = new_basic(json!({
+            "gloves": [{"zh": "巨人手套", "en": "Titan Gauntlets"}],
+            "rarePrefixes": [{"zh": "灾难", "en": "Doom"}],
+            "rareSuffixes": [{"zh": "之握", "en": "Grasp"}],
+        }));
+
+        assert_eq!(basic.trans_rare_name("灾难之握").unwrap(), "Doom Grasp");
+        assert_eq!(
+            basic
+                .trans_name_and_base_type("灾难之握", "巨人手套")
+                .unwrap(),
+            (String::from("Doom Grasp"), String::from("Titan Gauntlets"))
+        );
+        assert_eq!(
+            basic
+                .trans_name_and_base_type("未知之握", "巨人手套")
+                .unwrap(),
+            (String::from("Item"), String::from("Titan Gauntlets"))
+        );
+    }
+
+    #[test]
+    fn test_resolve_mod() {
+        let basic = new_basic(json!({
+            "stats": [{
+                "zh": "{1} 秒内回复 {0} 生命",
+                "en": "Recovers {0} Life over {1} Seconds",
+                "ids": ["base_life_recovery", "base_life_recovery_duration"],
+            }],
+        }));
+
+        let resolved = basic.resolve_mod("5 秒内回复 1000 生命").unwrap();
+        assert_eq!(resolved.en, "Recovers 1000 Life over 5 Seconds");
+        assert_eq!(
+            resolved.ids,
+            ["base_life_recovery", "base_life_recovery_duration"]
+        );
+        assert_eq!(resolved.params[&0].text, "1000");
+        assert_eq!(resolved.params[&1].number(), Some(5.0));
+        assert_eq!(resolved.stat.en, "Recovers {0} Life over {1} Seconds");
+
+        assert!(basic.resolve_mod("5 秒内回复 1000 魔力").is_none());
+    }
+
+    #[test]
+    fn test_resolve_sign_flipped_mod() {
+        let basic = new_basic(json!({
+            "stats": [
+                {"zh": "攻击速度提高 {0}%", "en": "{0}% increased Attack Speed"},
+                {"zh": "攻击速度额外提高 {0}%", "en": "{0}% more Attack Speed"},
+                {"zh": "增加 {0} 点护甲", "en": "+{0} to Armour"},
+            ],
+        }));
+
+        let resolved = basic.resolve_mod("攻击速度降低 10%").unwrap();
+        assert_eq!(resolved.en, "10% reduced Attack Speed");
+        assert!(resolved.sign_flipped);
+        assert_eq!(
+            basic.trans_mod("攻击速度额外降低 10%").unwrap(),
+            "10% less Attack Speed"
+        );
+        assert!(!basic.resolve_mod("攻击速度提高 10%").unwrap().sign_flipped);
+        assert!(basic.trans_mod("减少 10 点护甲").is_none());
+    }
+
+    #[test]
+    fn test_rank_mod_candidates() {
+        let basic = new_basic(json!({
+            "stats": [
+                {"zh": "{1} 秒内回复 {0} 生命", "en": "Recovers {0} Life over {1} Seconds"},
+                {"zh": "1 秒内回复 {0} 生命", "en": "Recovers {0} Life over 1 Second"},
+            ],
+        }));
+
+        let candidates = basic.rank_mod_candidates("1 秒内回复 1000 生命");
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[0].stat.en, "Recovers {0} Life over 1 Second");
+        assert!(candidates[0].score > candidates[1].score);
+        assert_eq!(
+            basic.trans_mod("1 秒内回复 1000 生命").unwrap(),
+            "Recovers 1000 Life over 1 Second"
+        );
+
+        let candidates = basic.rank_mod_candidates("5 秒内回复 1000 生命");
+        assert!(candidates[0].score > 0);
+        assert_eq!(candidates[1].score, 0);
+        assert!(candidates[1].resolved.is_none());
+        assert_eq!(
+            basic.trans_mod("5 秒内回复 1000 生命").unwrap(),
+            "Recovers 1000 Life over 5 Seconds"
+        );
+    }
+
+    #[test]
+    fn test_trans_gem_prop() {
+        let basic = new_basic(json!({
+            "properties": [
+                {"zh": "攻击", "en": "Attack"},
+                {"zh": "近战", "en": "Melee"},
+                {"zh": "消耗", "en": "Cost"},
+                {"zh": "{0} 魔力", "en": "{0} Mana"},
+                {"zh": "经验值", "en": "Experience"},
+            ],
+        }));
+
+        assert_eq!(basic.trans_gem_prop("等级").unwrap(), "Level");
+        assert_eq!(basic.trans_gem_prop("攻击, 近战").unwrap(), "Attack, Melee");
+        assert_eq!(basic.trans_gem_prop("攻击，近战").unwrap(), "Attack, Melee");
+        assert!(basic.trans_gem_prop("攻击, 未知").is_none());
+        assert_eq!(basic.trans_gem_prop("经验值").unwrap(), "Experience");
+
+        assert_eq!(
+            basic.trans_gem_prop_value("消耗", "10 魔力").unwrap(),
+            "10 Mana"
+        );
+        assert!(basic.trans_gem_prop_value("消耗", "1.00 秒").is_none());
+        assert!(basic.trans_gem_prop_value("等级", "20").is_none());
+    }
+}
@@ -1,31 +1,65 @@
+use indexmap::IndexSet;
 use log::warn;
-use regex::Regex;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use crate::model::{
-    items::{Item, Items},
+    items::{Item, ItemProperty, ItemRequirement, Items},
     passive_skills::PassiveSkills,
 };
 
-use super::Basic;
+use super::{
+    rule::{self, ItemRule, ItemsRule, Rules},
+    Basic,
+};
 
 pub struct Json {
     basic: Arc<Basic>,
+    item_rules: Rules<dyn ItemRule>,
+    items_rules: Rules<dyn ItemsRule>,
+    untranslated: Mutex<IndexSet<String>>,
 }
 
 impl Json {
     pub fn new(basic: Arc<Basic>) -> Json {
-        Json { basic }
+        Json {
+            basic,
+            item_rules: rule::default_item_rules(),
+            items_rules: rule::default_items_rules(),
+            untranslated: Mutex::new(IndexSet::new()),
+        }
+    }
+
+    /// The rules applied to each item before it is translated.
+    pub fn item_rules_mut(&mut self) -> &mut Rules<dyn ItemRule> {
+        &mut self.item_rules
+    }
+
+    /// The rules applied to items after they are translated.
+    pub fn items_rules_mut(&mut self) -> &mut Rules<dyn ItemsRule> {
+        &mut self.items_rules
+    }
+
+    /// The strings failed to be translated so far, e.g. "explicit mod, 最大生命 +10".
+    pub fn untranslated(&self) -> Vec<String> {
+        self.untranslated.lock().unwrap().iter().cloned().collect()
+    }
+
+    fn report_untranslated(&self, kind: &str, zh: &str) {
+        // e.g. the name of normal items is empty
+        if zh.is_empty() {
+            return;
+        }
+
+        warn!("should be translated: {0}, {1}", kind, zh);
+        self.untranslated
+            .lock()
+            .unwrap()
+            .insert(format!("{}, {}", kind, zh));
     }
 
     const ZH_THIEF_TRINKET: &str = "赏金猎人饰品";
-    const ZH_FORBIDDEN_FLESH: &str = "禁断之肉";
-    const ZH_FORBIDDEN_FLAME: &str = "禁断之火";
-    const ZH_PASSIVE_SKILL_ASCENDANT_ASSASSIN: &str = "暗影";
-    const ZH_PASSIVE_SKILL_ASCENDANT_ASSASSIN_FIXED: &str = "暗影（贵族）";
-    const ZH_CLASS_SCION: &str = "贵族";
 
-    pub const ZH_REQUIREMENT_NAME_CLASS: &str = "职业：";
+    const FRAME_TYPE_MAGIC: usize = 1;
 
     pub fn trans_items(&self, items: &mut Items) {
         items.items.retain(|x| self.is_pob_item(x));
@@ -49,41 +83,8 @@ impl Json {
     }
 
     fn pre_handle_item(&self, item: &mut Item) {
-        if item.name == Self::ZH_FORBIDDEN_FLAME || item.name == Self::ZH_FORBIDDEN_FLESH {
-            let mut matched = false;
-            if let Some(requirements) = &mut item.requirements {
-                for req in requirements {
-                    if req.name == Self::ZH_REQUIREMENT_NAME_CLASS {
-                        let value = &req.values[0].0;
-                        if value != Self::ZH_CLASS_SCION {
-                            matched = true;
-                        }
-                        break;
-                    }
-                }
-            }
-            // 职业：贵族
-            if matched {
-                if let Some(explicits) = &mut item.explicit_mods {
-                    for mod_str in explicits {
-                        if mod_str.ends_with(Self::ZH_PASSIVE_SKILL_ASCENDANT_ASSASSIN) {
-                            mod_str.truncate(
-                                mod_str.len() - Self::ZH_PASSIVE_SKILL_ASCENDANT_ASSASSIN.len(),
-                            );
-                            mod_str.push_str(Self::ZH_PASSIVE_SKILL_ASCENDANT_ASSASSIN_FIXED);
-                        }
-                    }
-                }
-            }
-        }
-
-        let re = Regex::new(r"^元素伤害(提高|降低) \d+%$").unwrap();
-        if let Some(enchants) = &mut item.enchant_mods {
-            for mod_str in enchants {
-                if re.is_match(mod_str) {
-                    mod_str.insert_str(0, "该武器的");
-                }
-            }
+        for rule in self.item_rules.iter() {
+            rule.apply(item);
         }
     }
 
@@ -95,41 +96,23 @@ impl Json {
             item.name = name;
             item.base_type = base_type;
         } else {
-            warn!("should be translated: item name, {0}", item.name);
-            warn!("should be translated: item base type, {0}", item.base_type);
+            self.report_untranslated("item name", &item.name);
+            self.report_untranslated("item base type", &item.base_type);
         }
 
-        let result = self.basic.trans_type_line(&item.type_line);
+        let result = match item.frame_type {
+            Self::FRAME_TYPE_MAGIC => self
+                .basic
+                .trans_magic_type_line(&item.type_line)
+                .or_else(|| self.basic.trans_type_line(&item.type_line)),
+            _ => self.basic.trans_type_line(&item.type_line),
+        };
         if let Some(type_line) = result {
             item.type_line = type_line;
         }
 
         if let Some(reqs) = &mut item.requirements {
-            for r in reqs {
-                let zh_name = r.name.clone();
-                let result = self.basic.trans_req_name(&r.name);
-                if let Some(name) = result {
-                    r.name = name;
-                } else {
-                    warn!("should be translated: requirement name, {0}", r.name);
-                }
-
-                for v in &mut r.values {
-                    let (_, result) = self.basic.trans_req(&zh_name, &v.0);
-                    if let Some(result) = result {
-                        v.0 = result;
-                    }
-                }
-
-                if let Some(suffix) = &r.suffix {
-                    let result = self.basic.trans_req_suffix(suffix);
-                    if let Some(result) = result {
-                        r.suffix = Some(result);
-                    } else {
-                        warn!("should be translated: requirement suffix, {0}", suffix);
-                    }
-                }
-            }
+            self.trans_requirements(reqs);
         }
 
         if let Some(props) = &mut item.properties {
@@ -139,7 +122,7 @@ impl Json {
                 if let Some(name) = result {
                     p.name = name;
                 } else {
-                    warn!("should be translated: property name, {0}", p.name);
+                    self.report_untranslated("property name", &p.name);
                 }
 
                 for p in &mut p.values {
@@ -167,7 +150,7 @@ impl Json {
                 if let Some(value) = value {
                     *mod_str = value;
                 } else {
-                    warn!("should be translated: enchant mod, {0}", mod_str);
+                    self.report_untranslated("enchant mod", mod_str);
                 }
             }
         }
@@ -178,7 +161,7 @@ impl Json {
                 if let Some(value) = value {
                     *mod_str = value;
                 } else {
-                    warn!("should be translated: implicit mod, {0}", mod_str);
+                    self.report_untranslated("implicit mod", mod_str);
                 }
             }
         }
@@ -189,7 +172,7 @@ impl Json {
                 if let Some(value) = value {
                     *mod_str = value;
                 } else {
-                    warn!("should be translated: explicit mod, {0}", mod_str);
+                    self.report_untranslated("explicit mod", mod_str);
                 }
             }
         }
@@ -200,7 +183,7 @@ impl Json {
                 if let Some(value) = value {
                     *mod_str = value;
                 } else {
-                    warn!("should be translated: crafted mod, {0}", mod_str);
+                    self.report_untranslated("crafted mod", mod_str);
                 }
             }
         }
@@ -211,7 +194,7 @@ impl Json {
                 if let Some(value) = value {
                     *mod_str = value;
                 } else {
-                    warn!("should be translated: utility mod, {0}", mod_str);
+                    self.report_untranslated("utility mod", mod_str);
                 }
             }
         }
@@ -222,7 +205,7 @@ impl Json {
                 if let Some(value) = value {
                     *mod_str = value;
                 } else {
-                    warn!("should be translated: fractured mod, {0}", mod_str);
+                    self.report_untranslated("fractured mod", mod_str);
                 }
             }
         }
@@ -233,7 +216,7 @@ impl Json {
                 if let Some(value) = value {
                     *mod_str = value;
                 } else {
-                    warn!("should be translated: scourge mod, {0}", mod_str);
+                    self.report_untranslated("scourge mod", mod_str);
                 }
             }
         }
@@ -244,7 +227,46 @@ impl Json {
                 if let Some(value) = value {
                     *mod_str = value;
                 } else {
-                    warn!("should be translated: crucible mod, {0}", mod_str);
+                    self.report_untranslated("crucible mod", mod_str);
+                }
+            }
+        }
+
+        if let Some(mods) = &mut item.mutated_mods {
+            for mod_str in mods.iter_mut() {
+                let value = self.basic.trans_mod(mod_str);
+                if let Some(value) = value {
+                    *mod_str = value;
+                } else {
+                    self.report_untranslated("mutated mod", mod_str);
+                }
+            }
+        }
+    }
+
+    fn trans_requirements(&self, reqs: &mut [ItemRequirement]) {
+        for r in reqs {
+            let zh_name = r.name.clone();
+            let result = self.basic.trans_req_name(&r.name);
+            if let Some(name) = result {
+                r.name = name;
+            } else {
+                self.report_untranslated("requirement name", &r.name);
+            }
+
+            for v in &mut r.values {
+                let (_, result) = self.basic.trans_req(&zh_name, &v.0);
+                if let Some(result) = result {
+                    v.0 = result;
+                }
+            }
+
+            if let Some(suffix) = &r.suffix {
+                let result = self.basic.trans_req_suffix(suffix);
+                if let Some(result) = result {
+                    r.suffix = Some(result);
+                } else {
+                    self.report_untranslated("requirement suffix", suffix);
                 }
             }
         }
@@ -255,14 +277,14 @@ impl Json {
         if let Some(base_type) = result {
             gem.base_type = base_type;
         } else {
-            warn!("should be translated: gem base_type, {0}", gem.base_type);
+            self.report_untranslated("gem base_type", &gem.base_type);
         }
 
         let result = self.basic.trans_gem(&gem.type_line);
         if let Some(type_line) = result {
             gem.type_line = type_line;
         } else {
-            warn!("should be translated: gem type_line, {0}", gem.type_line);
+            self.report_untranslated("gem type_line", &gem.type_line);
         }
 
         if let Some(hybrid) = &mut gem.hybrid {
@@ -270,10 +292,7 @@ impl Json {
             if let Some(name) = result {
                 hybrid.base_type_name = name;
             } else {
-                warn!(
-                    "should be translated: gem hybrid baseTypeName, {0}",
-                    hybrid.base_type_name
-                );
+                self.report_untranslated("gem hybrid baseTypeName", &hybrid.base_type_name);
             }
         }
 
@@ -282,22 +301,59 @@ impl Json {
             gem.type_line = type_line;
         }
 
+        if let Some(reqs) = &mut gem.requirements {
+            self.trans_requirements(reqs);
+        }
+
         if let Some(props) = &mut gem.properties {
-            for p in props {
-                let result = self.basic.trans_gem_prop(&p.name);
-                if let Some(name) = result {
-                    p.name = name;
+            self.trans_gem_props(props);
+        }
+        if let Some(props) = &mut gem.additional_properties {
+            self.trans_gem_props(props);
+        }
+
+        // descriptions are missing in old assets, they are left as is without reporting
+        if let Some(text) = &gem.sec_descr_text {
+            let result = self.basic.trans_gem_descr(text);
+            if let Some(result) = result {
+                gem.sec_descr_text = Some(result);
+            }
+        }
+
+        if let Some(mods) = &mut gem.explicit_mods {
+            for mod_str in mods.iter_mut() {
+                let value = self.basic.trans_mod(mod_str);
+                if let Some(value) = value {
+                    *mod_str = value;
+                } else {
+                    self.report_untranslated("gem explicit mod", mod_str);
+                }
+            }
+        }
+    }
+
+    fn trans_gem_props(&self, props: &mut [ItemProperty]) {
+        for p in props {
+            let zh_name = p.name.clone();
+            let result = self.basic.trans_gem_prop(&p.name);
+            if let Some(name) = result {
+                p.name = name;
+            } else {
+                self.report_untranslated("gem property name", &p.name);
+            }
+
+            for v in &mut p.values {
+                let result = self.basic.trans_gem_prop_value(&zh_name, &v.0);
+                if let Some(result) = result {
+                    v.0 = result;
                 }
             }
         }
     }
 
     fn post_handle_items(&self, items: &mut Items) {
-        // Added a temporary workaround to handle incorrect data from Tencent server.
-        // The sub-codes should be removed when the bug is fixed.
-        if items.character.league.contains("费西亚") {
-            items.character.class =
-                String::from(map_to_phrecia_character_class(&items.character.class))
+        for rule in self.items_rules.iter() {
+            rule.apply(items);
         }
     }
 
@@ -312,50 +368,32 @@ impl Json {
                 if let Some(name) = name {
                     o.name = name;
                 } else {
-                    warn!("should be translated: keystone, {0}", o.name);
+                    self.report_untranslated("keystone", &o.name);
                 }
             } else {
+                // tattoos, runegrafts and so on are base types
                 let name = self.basic.trans_base_type(&o.name);
                 if let Some(name) = name {
                     o.name = name;
+                } else if let Some(true) = o.is_tattoo {
+                    self.report_untranslated("tattoo", &o.name);
                 } else {
-                    warn!("should be translated: base_type, {0}", o.name);
+                    self.report_untranslated("override", &o.name);
                 }
             }
-        }
-    }
-}
 
-static ASCENDANCIES: [[&str; 4]; 7] = [
-    ["None", "Ascendant", "None", "None"],
-    ["None", "Juggernaut", "Berserker", "Chieftain"],
-    ["None", "Warden", "Deadeye", "Pathfinder"],
-    ["None", "Occultist", "Elementalist", "Necromancer"],
-    ["None", "Slayer", "Gladiator", "Champion"],
-    ["None", "Inquisitor", "Hierophant", "Guardian"],
-    ["None", "Assassin", "Trickster", "Saboteur"],
-];
-
-static PHRECIA_ASCENDANCIES: [[&str; 4]; 7] = [
-    ["None", "Scavenger", "None", "None"],
-    ["None", "Ancestral Commander", "Behemoth", "Antiquarian"],
-    ["None", "Wildspeaker", "Whisperer", "Daughter of Oshabi"],
-    ["None", "Harbinger", "Herald", "Bog Shaman"],
-    ["None", "Aristocrat", "Gambler", "Paladin"],
-    ["None", "Architect of Chaos", "Puppeteer", "Polytheist"],
-    ["None", "Servant of Arakaali", "Blind Prophet", "Surfcaster"],
-];
-
-// Temporary function used to resolve API errors of Tencent server
-pub fn map_to_phrecia_character_class(character_class: &str) -> &str {
-    for i in 0..ASCENDANCIES.len() {
-        for j in 0..ASCENDANCIES[i].len() {
-            if ASCENDANCIES[i][j] == character_class {
-                return PHRECIA_ASCENDANCIES[i][j];
+            if let Some(stats) = &mut o.stats {
+                for mod_str in stats.iter_mut() {
+                    let value = self.basic.trans_mod(mod_str);
+                    if let Some(value) = value {
+                        *mod_str = value;
+                    } else {
+                        self.report_untranslated("override stat", mod_str);
+                    }
+                }
             }
         }
     }
-    character_class
 }
 
 #[cfg(test)]
@@ -381,4 +419,108 @@ mod tests {
         let serialized = serde_json::to_string(&skills).unwrap();
         let _ = fs::write("test/passive_skills_rs.json", serialized);
     }
+
+    #[test]
+    fn test_trans_passive_skills() {
+        let translator = Json::new(super::super::basic::tests::new_basic(serde_json::json!({
+            "tattoos": [{"zh": "纳玛霍战争狂纹身", "en": "Tattoo of the Ngamahu Warmonger"}],
+            "grafts": [{"zh": "坚忍符文嫁接", "en": "Runegraft of Fortification"}],
+            "stats": [
+                {"zh": "力量 +{0}", "en": "+{0} to Strength"},
+                {"zh": "护甲提高 {0}%", "en": "{0}% increased Armour"},
+            ],
+        })));
+
+        let mut skills: PassiveSkills = serde_json::from_value(serde_json::json!({
+            "character": 1, "ascendancy": 0, "alternate_ascendancy": 0,
+            "hashes": [], "hashes_ex": [], "mastery_effects": {},
+            "skill_overrides": {
+                "100": {"name": "纳玛霍战争狂纹身", "isTattoo": true, "stats": ["力量 +8"]},
+                "200": {"name": "坚忍符文嫁接", "stats": ["护甲提高 20%"]},
+            },
+            "items": [], "jewel_data": {},
+        }))
+        .unwrap();
+        translator.trans_passive_skills(&mut skills);
+
+        let tattoo = &skills.skill_overrides[&100];
+        assert_eq!(tattoo.name, "Tattoo of the Ngamahu Warmonger");
+        assert_eq!(tattoo.stats.as_ref().unwrap(), &vec!["+8 to Strength"]);
+        let runegraft = &skills.skill_overrides[&200];
+        assert_eq!(runegraft.name, "Runegraft of Fortification");
+        assert_eq!(
+            runegraft.stats.as_ref().unwrap(),
+            &vec!["20% increased Armour"]
+        );
+        assert!(translator.untranslated().is_empty());
+    }
+
+    #[test]
+    fn test_trans_gem() {
+        let translator = Json::new(super::super::basic::tests::new_basic(serde_json::json!({
+            "gems": [{
+                "zh": "冰霜新星",
+                "en": "Ice Nova",
+                "descrZh": "一圈冰冷的寒冰从施法者处扩散开来。",
+                "descrEn": "A chilling circle of ice expands from the caster.",
+            }],
+        })));
+
+        let mut gem: Item = serde_json::from_value(serde_json::json!({
+            "name": "",
+            "typeLine": "冰霜新星",
+            "baseType": "冰霜新星",
+            "ilvl": 0,
+            "frameType": 4,
+            "secDescrText": "一圈冰冷的寒冰从施法者处扩散开来。",
+        }))
+        .unwrap();
+        translator.trans_gem(&mut gem);
+        assert_eq!(gem.base_type, "Ice Nova");
+        assert_eq!(
+            gem.sec_descr_text.unwrap(),
+            "A chilling circle of ice expands from the caster."
+        );
+
+        // the description is unknown
+        let mut gem: Item = serde_json::from_value(serde_json::json!({
+            "name": "",
+            "typeLine": "冰霜新星",
+            "baseType": "冰霜新星",
+            "ilvl": 0,
+            "frameType": 4,
+            "secDescrText": "未知描述",
+        }))
+        .unwrap();
+        translator.trans_gem(&mut gem);
+        assert_eq!(gem.sec_descr_text.unwrap(), "未知描述");
+        assert!(translator.untranslated().is_empty());
+    }
+
+    #[test]
+    fn test_untranslated() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Json>();
+
+        let translator = Json::new(super::super::basic::tests::new_basic(serde_json::json!({})));
+
+        let mut item: Item = serde_json::from_value(serde_json::json!({
+            "name": "",
+            "typeLine": "未知底子",
+            "baseType": "未知底子",
+            "ilvl": 86,
+            "frameType": 0,
+            "explicitMods": ["未知词缀", "未知词缀"],
+        }))
+        .unwrap();
+        translator.trans_item(&mut item);
+
+        assert_eq!(
+            translator.untranslated(),
+            vec![
+                "item base type, 未知底子".to_string(),
+                "explicit mod, 未知词缀".to_string(),
+            ]
+        );
+    }
 }
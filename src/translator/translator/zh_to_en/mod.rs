@@ -1,14 +1,17 @@
 mod basic;
 mod json;
+pub mod rule;
 
 use std::sync::Arc;
 
-pub use basic::Basic;
+pub use basic::{Basic, ResolvedMod, StatCandidate};
 pub use json::Json;
 
 use crate::{
     db::{assets::ASSETS_DATA, Assets},
-    translator::provider::{attribute, base_type, gem, passive_skill, property, requirement, stat},
+    translator::provider::{
+        affix, attribute, base_type, gem, passive_skill, property, requirement, stat,
+    },
 };
 
 /// Factory for basic and json translator.
@@ -21,6 +24,12 @@ impl Factory {
     pub fn new() -> Factory {
         let assets: Assets = serde_json::from_str(ASSETS_DATA).unwrap();
 
+        Self::from_assets(assets)
+    }
+
+    /// Create a new Factory from the given assets.
+    pub fn from_assets(assets: Assets) -> Factory {
+        let affix_provider = affix::Provider::new(assets.prefixes, assets.suffixes);
         let attribute_provider = attribute::Provider::new(assets.attributes);
         let basetype_provider = base_type::Provider::new(vec![
             assets.amulets,
@@ -37,21 +46,25 @@ impl Factory {
             assets.tinctures,
             assets.weapons,
             assets.tattoos,
+            assets.grafts,
         ]);
         let gem_provider = gem::Provider::new(assets.gems, assets.hybrid_skills);
         let passive_skill_provider =
             passive_skill::Provider::new(assets.notables, assets.keystones, assets.ascendant);
         let property_provider = property::Provider::new(assets.properties);
+        let rare_name_provider = affix::Provider::new(assets.rare_prefixes, assets.rare_suffixes);
         let requirement_provider =
             requirement::Provider::new(assets.requirements, assets.requirement_suffixes);
         let stat_provider = stat::Provider::new(assets.stats);
 
         let basic = Basic::new(
+            affix_provider,
             attribute_provider,
             basetype_provider,
             gem_provider,
             passive_skill_provider,
             property_provider,
+            rare_name_provider,
             requirement_provider,
             stat_provider,
         );
@@ -65,6 +78,11 @@ impl Factory {
         self.basic.clone()
     }
 
+    /// Get the basic translator to configure its rules, None if it has been shared.
+    pub fn basic_translator_mut(&mut self) -> Option<&mut Basic> {
+        Arc::get_mut(&mut self.basic)
+    }
+
     pub fn json_translator(&self) -> Json {
         Json::new(self.basic.clone())
     }
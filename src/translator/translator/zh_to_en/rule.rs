@@ -0,0 +1,845 @@
+//! Rules patch quirks of items and mods, e.g. bugs of Tencent server or special mods of leagues.
+//!
+//! Rules are registered in `Rules` by name, so they can be enabled or disabled one by one.
+
+use std::convert::TryFrom;
+
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::model::items::{Item, Items};
+
+use super::Basic;
+
+pub trait Rule: Send + Sync {
+    /// The unique name of the rule.
+    fn name(&self) -> &str;
+}
+
+/// The rule fixes an item before it is translated.
+pub trait ItemRule: Rule {
+    fn apply(&self, item: &mut Item);
+}
+
+/// The rule fixes items after they are translated.
+pub trait ItemsRule: Rule {
+    fn apply(&self, items: &mut Items);
+}
+
+/// The rule translates special mods which are not stats.
+pub trait ModRule: Rule {
+    /// Return None if the rule doesn't handle the mod or fails to translate it,
+    /// then the mod is translated by stats.
+    fn trans_mod(&self, basic: &Basic, mod_str: &str) -> Option<String>;
+}
+
+struct RuleEntry<R: ?Sized> {
+    rule: Box<R>,
+    enabled: bool,
+}
+
+/// The registry of rules, rules are applied in order.
+pub struct Rules<R: ?Sized> {
+    entries: Vec<RuleEntry<R>>,
+}
+
+impl<R: Rule + ?Sized> Default for Rules<R> {
+    fn default() -> Self {
+        Self { entries: vec![] }
+    }
+}
+
+impl<R: Rule + ?Sized> Rules<R> {
+    /// Register the rule, the rule with the same name is replaced.
+    pub fn register(&mut self, rule: Box<R>) {
+        self.entries.retain(|x| x.rule.name() != rule.name());
+        self.entries.push(RuleEntry {
+            rule,
+            enabled: true,
+        });
+    }
+
+    /// Enable or disable the rule, return false if there is no rule with the name.
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) -> bool {
+        for entry in &mut self.entries {
+            if entry.rule.name() == name {
+                entry.enabled = enabled;
+                return true;
+            }
+        }
+
+        false
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.entries.iter().map(|x| x.rule.name()).collect()
+    }
+
+    /// Iterate enabled rules.
+    pub fn iter(&self) -> impl Iterator<Item = &R> {
+        self.entries
+            .iter()
+            .filter(|x| x.enabled)
+            .map(|x| x.rule.as_ref())
+    }
+}
+
+pub fn default_item_rules() -> Rules<dyn ItemRule> {
+    let mut rules: Rules<dyn ItemRule> = Rules::default();
+    rules.register(Box::new(ForbiddenJewelAssassinRule));
+    for rule in load_replace_mod_rules(DEFAULT_REPLACE_MOD_RULES).unwrap() {
+        rules.register(Box::new(rule));
+    }
+
+    rules
+}
+
+pub fn default_items_rules() -> Rules<dyn ItemsRule> {
+    let mut rules: Rules<dyn ItemsRule> = Rules::default();
+    rules.register(Box::new(PhreciaClassRule));
+
+    rules
+}
+
+pub fn default_mod_rules() -> Rules<dyn ModRule> {
+    let mut rules: Rules<dyn ModRule> = Rules::default();
+    for rule in load_wrapped_mod_rules(DEFAULT_WRAPPED_MOD_RULES).unwrap() {
+        rules.register(Box::new(rule));
+    }
+    rules.register(Box::new(TimelessJewelModRule::new()));
+
+    rules
+}
+
+const ZH_FORBIDDEN_FLESH: &str = "禁断之肉";
+const ZH_FORBIDDEN_FLAME: &str = "禁断之火";
+const ZH_PASSIVE_SKILL_ASCENDANT_ASSASSIN: &str = "暗影";
+const ZH_PASSIVE_SKILL_ASCENDANT_ASSASSIN_FIXED: &str = "暗影（贵族）";
+const ZH_CLASS_SCION: &str = "贵族";
+const ZH_REQUIREMENT_NAME_CLASS: &str = "职业：";
+
+/// Forbidden Flame and Forbidden Flesh of Scion name the Assassin ascendant of Scion "暗影",
+/// it should be "暗影（贵族）".
+pub struct ForbiddenJewelAssassinRule;
+
+impl Rule for ForbiddenJewelAssassinRule {
+    fn name(&self) -> &str {
+        "forbidden_jewel_assassin"
+    }
+}
+
+impl ItemRule for ForbiddenJewelAssassinRule {
+    fn apply(&self, item: &mut Item) {
+        if item.name != ZH_FORBIDDEN_FLAME && item.name != ZH_FORBIDDEN_FLESH {
+            return;
+        }
+
+        let mut matched = false;
+        if let Some(requirements) = &mut item.requirements {
+            for req in requirements {
+                if req.name == ZH_REQUIREMENT_NAME_CLASS {
+                    let value = &req.values[0].0;
+                    if value != ZH_CLASS_SCION {
+                        matched = true;
+                    }
+                    break;
+                }
+            }
+        }
+        // 职业：贵族
+        if matched {
+            if let Some(explicits) = &mut item.explicit_mods {
+                for mod_str in explicits {
+                    if mod_str.ends_with(ZH_PASSIVE_SKILL_ASCENDANT_ASSASSIN) {
+                        mod_str.truncate(mod_str.len() - ZH_PASSIVE_SKILL_ASCENDANT_ASSASSIN.len());
+                        mod_str.push_str(ZH_PASSIVE_SKILL_ASCENDANT_ASSASSIN_FIXED);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The data of `ReplaceModRule`.
+#[derive(Deserialize)]
+pub struct ReplaceModRuleData {
+    pub name: String,
+    /// The field name of mods in the item JSON, e.g. "enchantMods".
+    pub mods: String,
+    pub pattern: String,
+    pub replacement: String,
+}
+
+/// The rule replaces mods matched by the regex pattern, it can be loaded from data.
+#[derive(Deserialize)]
+#[serde(try_from = "ReplaceModRuleData")]
+pub struct ReplaceModRule {
+    name: String,
+    mods: String,
+    re: Regex,
+    replacement: String,
+}
+
+impl TryFrom<ReplaceModRuleData> for ReplaceModRule {
+    type Error = regex::Error;
+
+    fn try_from(data: ReplaceModRuleData) -> Result<Self, Self::Error> {
+        Ok(ReplaceModRule {
+            name: data.name,
+            mods: data.mods,
+            re: Regex::new(&data.pattern)?,
+            replacement: data.replacement,
+        })
+    }
+}
+
+impl Rule for ReplaceModRule {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl ItemRule for ReplaceModRule {
+    fn apply(&self, item: &mut Item) {
+        let mods = match self.mods.as_str() {
+            "enchantMods" => &mut item.enchant_mods,
+            "implicitMods" => &mut item.implicit_mods,
+            "explicitMods" => &mut item.explicit_mods,
+            "craftedMods" => &mut item.crafted_mods,
+            "utilityMods" => &mut item.utility_mods,
+            "fracturedMods" => &mut item.fractured_mods,
+            "scourgeMods" => &mut item.scourge_mods,
+            "crucibleMods" => &mut item.crucible_mods,
+            "mutatedMods" => &mut item.mutated_mods,
+            _ => return,
+        };
+
+        if let Some(mods) = mods {
+            for mod_str in mods {
+                if self.re.is_match(mod_str) {
+                    *mod_str = self
+                        .re
+                        .replace_all(mod_str, self.replacement.as_str())
+                        .into_owned();
+                }
+            }
+        }
+    }
+}
+
+/// Load replace rules from JSON data.
+pub fn load_replace_mod_rules(data: &str) -> Result<Vec<ReplaceModRule>, serde_json::Error> {
+    serde_json::from_str(data)
+}
+
+static DEFAULT_REPLACE_MOD_RULES: &str = r#"[
+    {
+        "name": "weapon_elemental_damage_enchant",
+        "mods": "enchantMods",
+        "pattern": "^(元素伤害(提高|降低) \\d+%)$",
+        "replacement": "该武器的$1"
+    }
+]"#;
+
+/// Added a temporary workaround to handle incorrect data from Tencent server.
+/// The rule should be removed when the bug is fixed.
+pub struct PhreciaClassRule;
+
+impl Rule for PhreciaClassRule {
+    fn name(&self) -> &str {
+        "phrecia_class"
+    }
+}
+
+impl ItemsRule for PhreciaClassRule {
+    fn apply(&self, items: &mut Items) {
+        if items.character.league.contains("费西亚") {
+            items.character.class =
+                String::from(map_to_phrecia_character_class(&items.character.class))
+        }
+    }
+}
+
+static ASCENDANCIES: [[&str; 4]; 7] = [
+    ["None", "Ascendant", "None", "None"],
+    ["None", "Juggernaut", "Berserker", "Chieftain"],
+    ["None", "Warden", "Deadeye", "Pathfinder"],
+    ["None", "Occultist", "Elementalist", "Necromancer"],
+    ["None", "Slayer", "Gladiator", "Champion"],
+    ["None", "Inquisitor", "Hierophant", "Guardian"],
+    ["None", "Assassin", "Trickster", "Saboteur"],
+];
+
+static PHRECIA_ASCENDANCIES: [[&str; 4]; 7] = [
+    ["None", "Scavenger", "None", "None"],
+    ["None", "Ancestral Commander", "Behemoth", "Antiquarian"],
+    ["None", "Wildspeaker", "Whisperer", "Daughter of Oshabi"],
+    ["None", "Harbinger", "Herald", "Bog Shaman"],
+    ["None", "Aristocrat", "Gambler", "Paladin"],
+    ["None", "Architect of Chaos", "Puppeteer", "Polytheist"],
+    ["None", "Servant of Arakaali", "Blind Prophet", "Surfcaster"],
+];
+
+// Temporary function used to resolve API errors of Tencent server
+pub fn map_to_phrecia_character_class(character_class: &str) -> &str {
+    for i in 0..ASCENDANCIES.len() {
+        for j in 0..ASCENDANCIES[i].len() {
+            if ASCENDANCIES[i][j] == character_class {
+                return PHRECIA_ASCENDANCIES[i][j];
+            }
+        }
+    }
+    character_class
+}
+
+const ZH_SUPPORT_GEM_SUFFIX: &str = "（辅）";
+const EN_SUPPORT_GEM_SUFFIX: &str = " Support";
+
+/// The translator applied to the text wrapped by `WrappedModRule`.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum InnerKind {
+    Notable,
+    Keystone,
+    Ascendant,
+    Gem,
+    /// The support gem named without "（辅）", e.g. "多重打击" is translated to "Multistrike".
+    SupportGem,
+    Mod,
+}
+
+/// The data of `WrappedModRule`.
+#[derive(Deserialize)]
+pub struct WrappedModRuleData {
+    pub name: String,
+    /// The Zh template, `{0}` is the wrapped text and other placeholders are copied as they are,
+    /// e.g. "获得 {1} 级的主动技能【{0}】".
+    pub zh: String,
+    /// The En template, e.g. "Grants Level {1} {0} Skill".
+    pub en: String,
+    pub inner: InnerKind,
+}
+
+/// The rule translates mods which wrap a notable, keystone, ascendant, gem or another mod,
+/// it can be loaded from data.
+#[derive(Deserialize)]
+#[serde(try_from = "WrappedModRuleData")]
+pub struct WrappedModRule {
+    name: String,
+    re: Regex,
+    en: String,
+    inner: InnerKind,
+}
+
+impl TryFrom<WrappedModRuleData> for WrappedModRule {
+    type Error = regex::Error;
+
+    fn try_from(data: WrappedModRuleData) -> Result<Self, Self::Error> {
+        let placeholder = Regex::new(r"\{(\d+)\}").unwrap();
+        let mut pattern = String::from("^");
+        let mut last = 0;
+        for caps in placeholder.captures_iter(&data.zh) {
+            let m = caps.get(0).unwrap();
+            pattern.push_str(&regex::escape(&data.zh[last..m.start()]));
+            let index = &caps[1];
+            if index == "0" {
+                pattern.push_str("(?P<p0>.+)");
+            } else {
+                pattern.push_str(&format!(r"(?P<p{}>\S+)", index));
+            }
+            last = m.end();
+        }
+        pattern.push_str(&regex::escape(&data.zh[last..]));
+        pattern.push('$');
+
+        Ok(WrappedModRule {
+            name: data.name,
+            re: Regex::new(&pattern)?,
+            en: data.en,
+            inner: data.inner,
+        })
+    }
+}
+
+impl WrappedModRule {
+    fn trans_inner(&self, basic: &Basic, zh: &str) -> Option<String> {
+        match self.inner {
+            InnerKind::Notable => basic.trans_notable(zh),
+            InnerKind::Keystone => basic.trans_keystone(zh),
+            InnerKind::Ascendant => basic.trans_ascendant(zh),
+            InnerKind::Gem => basic.trans_gem(zh),
+            InnerKind::SupportGem => basic
+                .trans_gem(&format!("{}{}", zh, ZH_SUPPORT_GEM_SUFFIX))
+                .map(|x| x.trim_end_matches(EN_SUPPORT_GEM_SUFFIX).to_string()),
+            InnerKind::Mod => basic.trans_mod(zh),
+        }
+    }
+}
+
+impl Rule for WrappedModRule {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl ModRule for WrappedModRule {
+    fn trans_mod(&self, basic: &Basic, mod_str: &str) -> Option<String> {
+        let caps = self.re.captures(mod_str)?;
+        let inner = self.trans_inner(basic, &caps["p0"])?;
+
+        let placeholder = Regex::new(r"\{(\d+)\}").unwrap();
+        let en = placeholder.replace_all(&self.en, |x: &regex::Captures| {
+            if &x[1] == "0" {
+                inner.clone()
+            } else {
+                let name = format!("p{}", &x[1]);
+                caps.name(&name)
+                    .map(|m| m.as_str().to_string())
+                    .unwrap_or_default()
+            }
+        });
+
+        Some(en.into_owned())
+    }
+}
+
+/// Load wrapped mod rules from JSON data.
+pub fn load_wrapped_mod_rules(data: &str) -> Result<Vec<WrappedModRule>, serde_json::Error> {
+    serde_json::from_str(data)
+}
+
+static DEFAULT_WRAPPED_MOD_RULES: &str = r#"[
+    {
+        "name": "anointed_mod",
+        "zh": "配置 {0}",
+        "en": "Allocates {0}",
+        "inner": "notable"
+    },
+    {
+        "name": "forbidden_flame_mod",
+        "zh": "禁断之火上有匹配的词缀则配置 {0}",
+        "en": "Allocates {0} if you have the matching modifier on Forbidden Flame",
+        "inner": "ascendant"
+    },
+    {
+        "name": "forbidden_flesh_mod",
+        "zh": "禁断之肉上有匹配的词缀则配置 {0}",
+        "en": "Allocates {0} if you have the matching modifier on Forbidden Flesh",
+        "inner": "ascendant"
+    },
+    {
+        "name": "unique_enemy_presence_mod",
+        "zh": "有一个传奇怪物出现在你面前：{0}",
+        "en": "While a Unique Enemy is in your Presence, {0}",
+        "inner": "mod"
+    },
+    {
+        "name": "pinnacle_boss_presence_mod",
+        "zh": "有一个异界图鉴最终首领出现在你面前：{0}",
+        "en": "While a Pinnacle Atlas Boss is in your Presence, {0}",
+        "inner": "mod"
+    },
+    {
+        "name": "granted_skill_mod",
+        "zh": "获得 {1} 级的主动技能【{0}】",
+        "en": "Grants Level {1} {0} Skill",
+        "inner": "gem"
+    },
+    {
+        "name": "socketed_gems_supported_mod",
+        "zh": "插槽内的技能石被 {1} 级的 {0} 辅助",
+        "en": "Socketed Gems are Supported by Level {1} {0}",
+        "inner": "supportGem"
+    },
+    {
+        "name": "impossible_escape_mod",
+        "zh": "{0}范围内的天赋可以在未连结至天赋树的情况下配置",
+        "en": "Passives in Radius of {0} can be Allocated without being connected to your tree",
+        "inner": "keystone"
+    },
+    {
+        "name": "added_passive_skill_mod",
+        "zh": "{1} 个增加的天赋为 {0}",
+        "en": "{1} Added Passive Skill is {0}",
+        "inner": "notable"
+    },
+    {
+        "name": "added_small_passive_skills_mod",
+        "zh": "增加的小型天赋获得：{0}",
+        "en": "Added Small Passive Skills grant: {0}",
+        "inner": "mod"
+    },
+    {
+        "name": "added_small_passive_skills_also_mod",
+        "zh": "增加的小型天赋也获得：{0}",
+        "en": "Added Small Passive Skills also grant: {0}",
+        "inner": "mod"
+    }
+]"#;
+
+/// The seed lines of timeless jewels, `{seed}` is the seed and `{conqueror}` is the conqueror.
+///
+/// The En lines must be the same as what PoB parses.
+static TIMELESS_JEWEL_LINES: [(&str, &str); 6] = [
+    // Glorious Vanity
+    (
+        "以 {conqueror} 的名义用 {seed} 名祭品之血浸染",
+        "Bathed in the blood of {seed} sacrificed in the name of {conqueror}",
+    ),
+    // Lethal Pride
+    (
+        "在 {conqueror} 的领导下统帅 {seed} 名战士",
+        "Commanded leadership over {seed} warriors under {conqueror}",
+    ),
+    // Brutal Restraint
+    (
+        "在 {conqueror} 的阿卡拉中服役的 {seed} 名德卡拉",
+        "Denoted service of {seed} dekhara in the akhara of {conqueror}",
+    ),
+    // Militant Faith
+    (
+        "雕刻以颂扬被大圣堂骑士 {conqueror} 改信的 {seed} 名新信徒",
+        "Carved to glorify {seed} new faithful converted by High Templar {conqueror}",
+    ),
+    // Elegant Hubris
+    (
+        "委托铸造 {seed} 枚硬币以纪念 {conqueror}",
+        "Commissioned {seed} coins to commemorate {conqueror}",
+    ),
+    // Heroic Tragedy
+    (
+        "铭记 {conqueror} 一族 {seed} 件值得传颂的事迹",
+        "Remembrancing {seed} songworthy deeds by the line of {conqueror}",
+    ),
+];
+
+static TIMELESS_JEWEL_CONQUERORS: [(&str, &str); 19] = [
+    // Glorious Vanity
+    ("多里亚尼", "Doryani"),
+    ("希巴克瓦", "Xibaqua"),
+    ("阿华纳", "Ahuana"),
+    // Lethal Pride
+    ("卡奥", "Kaom"),
+    ("拉其塔", "Rakiata"),
+    ("阿克雅", "Akoya"),
+    // Brutal Restraint
+    ("阿斯纳斯", "Asenath"),
+    ("娜斯玛", "Nasima"),
+    ("巴巴拉", "Balbala"),
+    // Militant Faith
+    ("阿瓦留斯", "Avarius"),
+    ("多米纳斯", "Dominus"),
+    ("马克萨留斯", "Maxarius"),
+    // Elegant Hubris
+    ("卡迪罗", "Cadiro"),
+    ("维多里奥", "Victario"),
+    ("奇塔斯", "Chitus"),
+    ("卡斯皮罗", "Caspiro"),
+    // Heroic Tragedy
+    ("沃拉娜", "Vorana"),
+    ("乌特雷德", "Uhtred"),
+    ("梅德维德", "Medved"),
+];
+
+/// Translate the seed lines of timeless jewels, the conqueror is translated by a name table.
+pub struct TimelessJewelModRule {
+    lines: Vec<(Regex, &'static str)>,
+}
+
+impl TimelessJewelModRule {
+    pub fn new() -> TimelessJewelModRule {
+        let lines = TIMELESS_JEWEL_LINES
+            .iter()
+            .map(|(zh, en)| {
+                let pattern = regex::escape(zh)
+                    .replace(r"\{seed\}", r"(?P<seed>\d+)")
+                    .replace(r"\{conqueror\}", r"(?P<conqueror>\S+)");
+                (Regex::new(&format!("^{}$", pattern)).unwrap(), *en)
+            })
+            .collect();
+
+        TimelessJewelModRule { lines }
+    }
+
+    pub fn trans_conqueror(zh: &str) -> Option<&'static str> {
+        TIMELESS_JEWEL_CONQUERORS
+            .iter()
+            .find(|(x, _)| *x == zh)
+            .map(|(_, en)| *en)
+    }
+}
+
+impl Default for TimelessJewelModRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Rule for TimelessJewelModRule {
+    fn name(&self) -> &str {
+        "timeless_jewel_mod"
+    }
+}
+
+impl ModRule for TimelessJewelModRule {
+    fn trans_mod(&self, _basic: &Basic, mod_str: &str) -> Option<String> {
+        for (re, en) in &self.lines {
+            if let Some(caps) = re.captures(mod_str) {
+                let conqueror = Self::trans_conqueror(&caps["conqueror"])?;
+                return Some(
+                    en.replace("{seed}", &caps["seed"])
+                        .replace("{conqueror}", conqueror),
+                );
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::super::basic::tests::new_basic;
+    use super::*;
+
+    #[test]
+    fn test_rules() {
+        let mut rules = default_mod_rules();
+        assert_eq!(rules.names()[0], "anointed_mod");
+
+        let count = rules.iter().count();
+        assert!(rules.set_enabled("anointed_mod", false));
+        assert!(!rules.set_enabled("unknown", false));
+        assert_eq!(rules.iter().count(), count - 1);
+
+        let rule = load_wrapped_mod_rules(
+            r#"[{"name": "anointed_mod", "zh": "配置 {0}", "en": "Allocates {0}", "inner": "notable"}]"#,
+        )
+        .unwrap()
+        .pop()
+        .unwrap();
+        rules.register(Box::new(rule));
+        assert_eq!(rules.iter().count(), count);
+        assert_eq!(rules.names().len(), count);
+        assert_eq!(rules.names()[count - 1], "anointed_mod");
+    }
+
+    #[test]
+    fn test_wrapped_mod_rule() {
+        let basic = new_basic(json!({
+            "notables": [{"id": "1", "zh": "丰沃", "en": "Fertile Mind"}],
+            "keystones": [{"id": "2", "zh": "移形换影", "en": "Ghost Dance"}],
+            "ascendant": [{"id": "3", "zh": "暗影（贵族）", "en": "Assassin"}],
+            "gems": [
+                {"zh": "召唤幽狼", "en": "Summon Spectral Wolf"},
+                {"zh": "多重打击（辅）", "en": "Multistrike Support"},
+            ],
+            "stats": [{"zh": "攻击速度提高 {0}%", "en": "{0}% increased Attack Speed"}],
+        }));
+
+        let cases = [
+            ("配置 丰沃", "Allocates Fertile Mind"),
+            (
+                "禁断之火上有匹配的词缀则配置 暗影（贵族）",
+                "Allocates Assassin if you have the matching modifier on Forbidden Flame",
+            ),
+            (
+                "有一个传奇怪物出现在你面前：攻击速度提高 10%",
+                "While a Unique Enemy is in your Presence, 10% increased Attack Speed",
+            ),
+            (
+                "获得 20 级的主动技能【召唤幽狼】",
+                "Grants Level 20 Summon Spectral Wolf Skill",
+            ),
+            (
+                "插槽内的技能石被 30 级的 多重打击 辅助",
+                "Socketed Gems are Supported by Level 30 Multistrike",
+            ),
+            (
+                "移形换影范围内的天赋可以在未连结至天赋树的情况下配置",
+                "Passives in Radius of Ghost Dance can be Allocated without being connected to your tree",
+            ),
+        ];
+        for (zh, en) in cases {
+            assert_eq!(basic.trans_mod(zh).unwrap(), en);
+        }
+
+        // the mod is wrapped, but the wrapped text is unknown
+        assert!(basic.trans_mod("配置 未知").is_none());
+    }
+
+    #[test]
+    fn test_wrapped_mod_fallback() {
+        let basic = new_basic(json!({
+            "stats": [
+                {"zh": "获得 {0} 级的主动技能【召唤野兽犀牛】", "en": "Grants Level {0} Summon Bestial Rhoa Skill"},
+                {"zh": "插槽内的技能石被 {0} 级的 未知 辅助", "en": "Socketed Gems are Supported by Level {0} Unknown"},
+            ],
+        }));
+
+        // the granted skill is not a gem, the full line is a stat
+        assert_eq!(
+            basic
+                .trans_mod("获得 20 级的主动技能【召唤野兽犀牛】")
+                .unwrap(),
+            "Grants Level 20 Summon Bestial Rhoa Skill"
+        );
+        assert_eq!(
+            basic
+                .trans_mod("插槽内的技能石被 10 级的 未知 辅助")
+                .unwrap(),
+            "Socketed Gems are Supported by Level 10 Unknown"
+        );
+    }
+
+    #[test]
+    fn test_cluster_jewel_mod() {
+        let basic = new_basic(json!({
+            "notables": [{"id": "1", "zh": "疾速之刃", "en": "Fuel the Fight"}],
+            "stats": [{"zh": "攻击速度提高 {0}%", "en": "{0}% increased Attack Speed"}],
+        }));
+
+        let cases = [
+            (
+                "1 个增加的天赋为 疾速之刃",
+                "1 Added Passive Skill is Fuel the Fight",
+            ),
+            (
+                "增加的小型天赋获得：攻击速度提高 10%",
+                "Added Small Passive Skills grant: 10% increased Attack Speed",
+            ),
+            (
+                "增加的小型天赋也获得：攻击速度提高 2%",
+                "Added Small Passive Skills also grant: 2% increased Attack Speed",
+            ),
+        ];
+        for (zh, en) in cases {
+            assert_eq!(basic.trans_mod(zh).unwrap(), en);
+        }
+    }
+
+    #[test]
+    fn test_cluster_jewel_mod_fallback() {
+        let basic = new_basic(json!({
+            "stats": [
+                {"zh": "{0} 个增加的天赋为 未知天赋", "en": "{0} Added Passive Skill is Unknown Notable"},
+                {"zh": "增加的小型天赋获得：未知词缀", "en": "Added Small Passive Skills grant: Unknown Mod"},
+            ],
+        }));
+
+        // the notable or the inner mod is unknown, the full line is a stat
+        assert_eq!(
+            basic.trans_mod("1 个增加的天赋为 未知天赋").unwrap(),
+            "1 Added Passive Skill is Unknown Notable"
+        );
+        assert_eq!(
+            basic.trans_mod("增加的小型天赋获得：未知词缀").unwrap(),
+            "Added Small Passive Skills grant: Unknown Mod"
+        );
+    }
+
+    #[test]
+    fn test_replace_mod_rule() {
+        let rules = load_replace_mod_rules(DEFAULT_REPLACE_MOD_RULES).unwrap();
+        let mut item: Item = serde_json::from_value(serde_json::json!({
+            "name": "",
+            "typeLine": "",
+            "baseType": "",
+            "ilvl": 1,
+            "frameType": 0,
+            "enchantMods": ["元素伤害提高 10%", "元素伤害降低 10%", "攻击速度提高 10%"],
+        }))
+        .unwrap();
+
+        rules[0].apply(&mut item);
+        assert_eq!(
+            item.enchant_mods.unwrap(),
+            vec![
+                "该武器的元素伤害提高 10%",
+                "该武器的元素伤害降低 10%",
+                "攻击速度提高 10%"
+            ]
+        );
+
+        assert!(load_replace_mod_rules(
+            r#"[{"name": "bad", "mods": "explicitMods", "pattern": "(", "replacement": ""}]"#
+        )
+        .is_err());
+    }
+
+    fn assert_timeless_jewel_mod(zh: &str, en: &str) {
+        let basic = new_basic(json!({}));
+        assert_eq!(basic.trans_mod(zh).unwrap(), en);
+    }
+
+    #[test]
+    fn test_glorious_vanity_mod() {
+        assert_timeless_jewel_mod(
+            "以 多里亚尼 的名义用 1234 名祭品之血浸染",
+            "Bathed in the blood of 1234 sacrificed in the name of Doryani",
+        );
+    }
+
+    #[test]
+    fn test_lethal_pride_mod() {
+        assert_timeless_jewel_mod(
+            "在 卡奥 的领导下统帅 10000 名战士",
+            "Commanded leadership over 10000 warriors under Kaom",
+        );
+    }
+
+    #[test]
+    fn test_brutal_restraint_mod() {
+        assert_timeless_jewel_mod(
+            "在 娜斯玛 的阿卡拉中服役的 500 名德卡拉",
+            "Denoted service of 500 dekhara in the akhara of Nasima",
+        );
+    }
+
+    #[test]
+    fn test_militant_faith_mod() {
+        assert_timeless_jewel_mod(
+            "雕刻以颂扬被大圣堂骑士 多米纳斯 改信的 2000 名新信徒",
+            "Carved to glorify 2000 new faithful converted by High Templar Dominus",
+        );
+    }
+
+    #[test]
+    fn test_elegant_hubris_mod() {
+        assert_timeless_jewel_mod(
+            "委托铸造 160000 枚硬币以纪念 卡斯皮罗",
+            "Commissioned 160000 coins to commemorate Caspiro",
+        );
+    }
+
+    #[test]
+    fn test_heroic_tragedy_mod() {
+        assert_timeless_jewel_mod(
+            "铭记 乌特雷德 一族 7000 件值得传颂的事迹",
+            "Remembrancing 7000 songworthy deeds by the line of Uhtred",
+        );
+    }
+
+    #[test]
+    fn test_timeless_jewel_mod_fallback() {
+        let basic = new_basic(json!({
+            "stats": [{
+                "zh": "以 新征服者 的名义用 {0} 名祭品之血浸染",
+                "en": "Bathed in the blood of {0} sacrificed in the name of New Conqueror",
+            }],
+        }));
+
+        // the conqueror is unknown, the full line is a stat
+        assert_eq!(
+            basic
+                .trans_mod("以 新征服者 的名义用 1234 名祭品之血浸染")
+                .unwrap(),
+            "Bathed in the blood of 1234 sacrificed in the name of New Conqueror"
+        );
+        assert!(basic
+            .trans_mod("以 未知 的名义用 1234 名祭品之血浸染")
+            .is_none());
+    }
+}
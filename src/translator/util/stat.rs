@@ -58,27 +58,79 @@ impl Template {
         }
     }
 
-    // parse_params parses the modifier and returns positional parameters.
-    pub fn parse_params(&self, modifier: &str) -> Option<HashMap<i32, String>> {
+    /// The specificity of the template, it is the count of literal characters
+    /// plus the count of fixed numbers, e.g. "+1" in "{0}% chance to grant +1 Rage".
+    ///
+    /// When several templates parse a modifier, the more specific one is preferred.
+    pub fn specificity(&self) -> usize {
+        let re = Regex::new(r"\d+").unwrap();
+        self.segments
+            .iter()
+            .map(|x| x.chars().count() + re.find_iter(x).count())
+            .sum()
+    }
+
+    /// The pattern of a typed parameter: a range like "(10-20)" or "10 到 20", or a signed number.
+    const PARAM_PATTERN: &str = r"(\(-?\d+(?:\.\d+)?-\d+(?:\.\d+)?\)|[+-]?\d+(?:\.\d+)?|[+-]?\d+(?:\.\d+)? 到 [+-]?\d+(?:\.\d+)?)";
+
+    fn parse_with(&self, modifier: &str, param_pattern: &str) -> Option<Vec<String>> {
         let joined = self
             .segments
             .iter()
             .map(|x| regex::escape(x))
             .collect::<Vec<String>>()
-            .join(r"(\S+)");
+            .join(param_pattern);
         let re = Regex::new(&format!("^{}$", joined)).unwrap();
-        let matches = re.captures(modifier);
+        let caps = re.captures(modifier)?;
+
+        Some((1..caps.len()).map(|i| String::from(&caps[i])).collect())
+    }
+
+    /// parse_params parses the modifier and returns typed positional parameters.
+    ///
+    /// Parameters must be numbers or ranges, a signed number is rejected if the template
+    /// already has a sign before the parameter.
+    pub fn parse_params(&self, modifier: &str) -> Option<HashMap<i32, Param>> {
+        let values = self.parse_with(modifier, Self::PARAM_PATTERN)?;
 
-        if let Some(caps) = matches {
-            let mut params_map: HashMap<i32, String> = HashMap::new();
-            for i in 1..caps.len() {
-                let actual_param = String::from(&caps[i]);
-                params_map.insert(self.param_nums[i - 1], actual_param);
+        let mut params_map: HashMap<i32, Param> = HashMap::new();
+        for (i, text) in values.into_iter().enumerate() {
+            let before = &self.segments[i];
+            let after = &self.segments[i + 1];
+
+            let signed = text.starts_with('+') || text.starts_with('-');
+            if signed && (before.ends_with('+') || before.ends_with('-')) {
+                return None;
             }
 
-            return Some(params_map);
-        };
-        None
+            let kind = if text.starts_with('(') || text.contains(Param::ZH_RANGE_SEPARATOR) {
+                ParamKind::Range
+            } else if after.starts_with('%') {
+                ParamKind::Percent
+            } else if text.contains('.') {
+                ParamKind::Decimal
+            } else {
+                ParamKind::Integer
+            };
+
+            params_map.insert(self.param_nums[i], Param { kind, text });
+        }
+
+        Some(params_map)
+    }
+
+    /// parse_text_params parses the modifier and returns positional parameters,
+    /// a parameter may be any text without spaces, e.g. names in properties.
+    pub fn parse_text_params(&self, modifier: &str) -> Option<HashMap<i32, String>> {
+        let values = self.parse_with(modifier, r"(\S+)")?;
+
+        Some(
+            values
+                .into_iter()
+                .enumerate()
+                .map(|(i, x)| (self.param_nums[i], x))
+                .collect(),
+        )
     }
 
     pub fn render(&self, params_map: HashMap<i32, String>) -> String {
@@ -95,6 +147,63 @@ impl Template {
 
         buf.join("")
     }
+
+    /// Render typed parameters.
+    ///
+    /// The sign of a parameter replaces the sign of the template, e.g. "-5" in "+{0} to Strength"
+    /// is rendered as "-5 to Strength". Decimals are kept as they are, ranges use "to".
+    pub fn render_params(&self, params_map: &HashMap<i32, Param>) -> String {
+        let mut buf = String::new();
+        for i in 0..self.param_nums.len() {
+            let segment = &self.segments[i];
+            let param = params_map.get(&self.param_nums[i]);
+            match param {
+                Some(param) => {
+                    let text = param.text.replace(Param::ZH_RANGE_SEPARATOR, " to ");
+                    if (text.starts_with('+') || text.starts_with('-'))
+                        && (segment.ends_with('+') || segment.ends_with('-'))
+                    {
+                        buf.push_str(&segment[..segment.len() - 1]);
+                    } else {
+                        buf.push_str(segment);
+                    }
+                    buf.push_str(&text);
+                }
+                None => buf.push_str(segment),
+            }
+        }
+
+        buf.push_str(self.segments.last().unwrap());
+
+        buf
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParamKind {
+    Integer,
+    Decimal,
+    Percent,
+    Range,
+}
+
+/// The typed parameter parsed from a modifier, `text` is what the modifier shows, e.g. "+10".
+#[derive(Debug, Clone, PartialEq)]
+pub struct Param {
+    pub kind: ParamKind,
+    pub text: String,
+}
+
+impl Param {
+    const ZH_RANGE_SEPARATOR: &str = " 到 ";
+
+    /// Return the number of the parameter, ranges have no number.
+    pub fn number(&self) -> Option<f64> {
+        match self.kind {
+            ParamKind::Range => None,
+            _ => self.text.trim_start_matches('+').parse::<f64>().ok(),
+        }
+    }
 }
 
 mod tests {
@@ -117,7 +226,56 @@ mod tests {
         let zh_tmpl = Template::new("{1} 秒内回复 {0} 生命");
         let en_tmpl = Template::new("Recovers {0} Life over {1} Seconds");
 
-        let out = en_tmpl.render(zh_tmpl.parse_params(zh_mod).unwrap());
+        let out = en_tmpl.render_params(&zh_tmpl.parse_params(zh_mod).unwrap());
         assert_eq!(out, "Recovers 1000 Life over 5 Seconds");
     }
+
+    #[test]
+    fn test_specificity() {
+        use super::Template;
+
+        let general = Template::new("{0} 秒内回复 {1} 生命");
+        let specific = Template::new("1 秒内回复 {0} 生命");
+        assert!(specific.specificity() > general.specificity());
+    }
+
+    #[test]
+    fn test_typed_params() {
+        use super::{ParamKind, Template};
+
+        let zh_tmpl = Template::new("{0} 力量");
+        let en_tmpl = Template::new("+{0} to Strength");
+        let params = zh_tmpl.parse_params("+10 力量").unwrap();
+        assert_eq!(params[&0].kind, ParamKind::Integer);
+        assert_eq!(params[&0].number(), Some(10.0));
+        assert_eq!(en_tmpl.render_params(&params), "+10 to Strength");
+        let params = zh_tmpl.parse_params("-5 力量").unwrap();
+        assert_eq!(en_tmpl.render_params(&params), "-5 to Strength");
+        assert!(zh_tmpl.parse_params("力量 力量").is_none());
+
+        let zh_tmpl = Template::new("+{0} 力量");
+        assert!(zh_tmpl.parse_params("++10 力量").is_none());
+
+        let zh_tmpl = Template::new("持续 {0} 秒");
+        let en_tmpl = Template::new("{0} seconds duration");
+        let params = zh_tmpl.parse_params("持续 0.50 秒").unwrap();
+        assert_eq!(params[&0].kind, ParamKind::Decimal);
+        assert_eq!(en_tmpl.render_params(&params), "0.50 seconds duration");
+
+        let zh_tmpl = Template::new("攻击速度加快 {0}%");
+        let params = zh_tmpl.parse_params("攻击速度加快 (10-20)%").unwrap();
+        assert_eq!(params[&0].kind, ParamKind::Range);
+        assert_eq!(params[&0].number(), None);
+        let params = zh_tmpl.parse_params("攻击速度加快 15%").unwrap();
+        assert_eq!(params[&0].kind, ParamKind::Percent);
+
+        let zh_tmpl = Template::new("造成 {0} 点物理伤害");
+        let en_tmpl = Template::new("Deals {0} Physical Damage");
+        let params = zh_tmpl.parse_params("造成 10 到 20 点物理伤害").unwrap();
+        assert_eq!(params[&0].kind, ParamKind::Range);
+        assert_eq!(
+            en_tmpl.render_params(&params),
+            "Deals 10 to 20 Physical Damage"
+        );
+    }
 }